@@ -1,8 +1,18 @@
 use crate::models::*;
 use crate::storage::Storage;
+use crate::todotxt;
+use crate::csv::{self, CsvColumnMapping};
+use crate::org;
+use crate::todoist;
+use crate::mstodo;
+use crate::report::{self, ReportOptions};
+use crate::pomodoro::{self, Pomodoro, PomodoroStatus};
+use crate::stats::{self, Stats, StatsRange};
+use crate::templates;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Mutex;
-use tauri::{State, Window, PhysicalPosition};
-use chrono::Utc;
+use tauri::{AppHandle, Manager, State, Window, PhysicalPosition};
+use chrono::{DateTime, Local, Utc};
 use auto_launch::AutoLaunch;
 #[cfg(target_os = "linux")]
 use std::fs;
@@ -12,127 +22,1394 @@ use std::path::PathBuf;
 pub struct AppState {
     pub data: Mutex<AppData>,
     pub storage: Storage,
+    // 加锁顺序：先 data 后 pomodoro
+    pub pomodoro: Mutex<Pomodoro>,
 }
 
+// 托盘提示显示正在计时的待办
+pub fn update_tray_tooltip(app: &AppHandle, data: &AppData) {
+    let tooltip = match data.running_timer() {
+        Some(entry) => {
+            let title = data.todos.iter()
+                .find(|t| t.id == entry.todo_id)
+                .map(|t| t.title.as_str())
+                .unwrap_or("");
+            format!("XhinkingTodo - {} ({})", title, entry.start.with_timezone(&Local).format("%H:%M"))
+        }
+        None => "XhinkingTodo".to_string(),
+    };
+    if let Err(e) = app.tray_handle().set_tooltip(&tooltip) {
+        eprintln!("Failed to set tray tooltip: {}", e);
+    }
+}
+
+// 解析 RFC 3339 日期参数；空字符串表示清除
+fn parse_date_arg(value: &str) -> Result<Option<DateTime<Utc>>, String> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|d| Some(d.with_timezone(&Utc)))
+        .map_err(|e| format!("Invalid date format: {}", e))
+}
+
+// 通知前端哪些待办因 blocker 完成或删除而解除阻塞
+fn emit_unblocked(app: &AppHandle, blocker_id: &str, todo_ids: Vec<String>) {
+    if todo_ids.is_empty() {
+        return;
+    }
+    let payload = TodosUnblocked {
+        blocker_id: blocker_id.to_string(),
+        todo_ids,
+    };
+    if let Err(e) = app.emit_all("todos-unblocked", payload) {
+        eprintln!("Failed to emit todos-unblocked: {}", e);
+    }
+}
+
+// 通知前端哪些待办和分组发生了变化（新增、修改或删除）
+pub fn emit_data_changed(app: &AppHandle, todo_ids: Vec<String>, group_ids: Vec<String>) {
+    if todo_ids.is_empty() && group_ids.is_empty() {
+        return;
+    }
+    emit_data_event(app, DataChanged { todo_ids, group_ids, reload: false });
+}
+
+// 导入等大范围变更后通知前端重新加载全部数据
+fn emit_data_reload(app: &AppHandle) {
+    emit_data_event(app, DataChanged { reload: true, ..Default::default() });
+}
+
+fn emit_data_event(app: &AppHandle, payload: DataChanged) {
+    if let Err(e) = app.emit_all("data-changed", payload) {
+        eprintln!("Failed to emit data-changed: {}", e);
+    }
+}
+
+// 排序前记录各待办的顺序值；插入时没有间隔会重排整组兄弟待办
+fn order_snapshot(data: &AppData) -> Vec<(String, f64)> {
+    data.todos.iter().map(|t| (t.id.clone(), t.order)).collect()
+}
+
+// 与快照相比顺序变化或新增的待办（排序只会在末尾追加，不会删除待办）
+fn reordered_since(data: &AppData, snapshot: &[(String, f64)]) -> Vec<String> {
+    data.todos.iter().enumerate()
+        .filter(|(index, t)| {
+            snapshot.get(*index).map(|(id, order)| id != &t.id || *order != t.order).unwrap_or(true)
+        })
+        .map(|(_, t)| t.id.clone())
+        .collect()
+}
+
+/// 获取所有数据；归档的待办默认不返回，通过 list_archived 分页浏览
+#[tauri::command]
+pub fn get_all_data(include_archived: Option<bool>, state: State<AppState>) -> Result<AppData, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let mut all = data.clone();
+    if !include_archived.unwrap_or(false) {
+        all.todos.retain(|t| !t.archived);
+    }
+    Ok(all)
+}
+
+/// 列出待办，按分组树和父子关系排列；group_id 会覆盖筛选条件中的分组，未传筛选条件时不含归档的待办
+#[tauri::command]
+pub fn list_todos(
+    group_id: Option<String>,
+    filter: Option<TodoFilter>,
+    state: State<AppState>,
+) -> Result<Vec<TodoItem>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let mut filter = filter.unwrap_or_else(|| TodoFilter {
+        include_archived: false,
+        ..Default::default()
+    });
+    if let Some(group_id) = group_id {
+        filter.group_ids = Some(vec![group_id]);
+    }
+    let filter = data.expand_group_filter(&filter);
+    
+    let todos: Vec<TodoItem> = data.todos.iter()
+        .filter(|t| filter.matches(t))
+        .cloned()
+        .collect();
+    
+    Ok(data.sort_in_tree(todos))
+}
+
+#[tauri::command]
+pub fn get_todo(id: String, state: State<AppState>) -> Result<TodoItem, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    data.todos.iter()
+        .find(|t| t.id == id)
+        .cloned()
+        .ok_or_else(|| "Todo not found".to_string())
+}
+
+/// 批量获取待办，查不到的 id（已删除）直接跳过
+#[tauri::command]
+pub fn get_todos(ids: Vec<String>, state: State<AppState>) -> Result<Vec<TodoItem>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let ids: BTreeSet<&str> = ids.iter().map(|id| id.as_str()).collect();
+    Ok(data.todos.iter()
+        .filter(|t| ids.contains(t.id.as_str()))
+        .cloned()
+        .collect())
+}
+
+/// 按树的顺序列出所有分组
+#[tauri::command]
+pub fn list_groups(state: State<AppState>) -> Result<Vec<TodoGroup>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    Ok(data.group_tree().into_iter().map(|(_, g)| g.clone()).collect())
+}
+
+/// 分页列出归档的待办，按归档时间从新到旧；from/to 限定归档时间，每页最多 500 条
+#[tauri::command]
+pub fn list_archived(
+    group_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+    state: State<AppState>,
+) -> Result<ArchivedPage, String> {
+    let from = from.as_deref().map(parse_date_arg).transpose()?.flatten();
+    let to = to.as_deref().map(parse_date_arg).transpose()?.flatten();
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size.unwrap_or(50).clamp(1, 500);
+    
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let mut archived: Vec<&TodoItem> = data.todos.iter()
+        .filter(|t| t.archived)
+        .filter(|t| group_id.as_ref().map(|g| &t.group_id == g).unwrap_or(true))
+        .filter(|t| from.map(|f| t.archived_at.map(|a| a >= f).unwrap_or(false)).unwrap_or(true))
+        .filter(|t| to.map(|end| t.archived_at.map(|a| a <= end).unwrap_or(false)).unwrap_or(true))
+        .collect();
+    // 缺少归档时间的旧数据排在最后
+    archived.sort_by_key(|t| std::cmp::Reverse(t.archived_at));
+    
+    Ok(ArchivedPage {
+        total: archived.len(),
+        todos: archived.into_iter()
+            .skip((page - 1).saturating_mul(page_size))
+            .take(page_size)
+            .cloned()
+            .collect(),
+        page,
+        page_size,
+    })
+}
+
+/// 批量取消归档，返回更新后的待办
+#[tauri::command]
+pub fn unarchive_todos(ids: Vec<String>, app: AppHandle, state: State<AppState>) -> Result<Vec<TodoItem>, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let now = Utc::now();
+    let mut restored = Vec::new();
+    for todo in data.todos.iter_mut().filter(|t| t.archived && ids.contains(&t.id)) {
+        todo.record_change("archived", Some(true.to_string()), Some(false.to_string()));
+        todo.archived = false;
+        todo.archived_at = None;
+        todo.updated_at = now;
+        restored.push(todo.clone());
+    }
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, restored.iter().map(|t| t.id.clone()).collect(), Vec::new());
+    Ok(restored)
+}
+
+/// 永久删除归档的待办，未归档的 id 会被忽略；返回删除的 id
+#[tauri::command]
+pub fn purge_archived(ids: Vec<String>, app: AppHandle, state: State<AppState>) -> Result<Vec<String>, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let purged: Vec<String> = data.todos.iter()
+        .filter(|t| t.archived && ids.contains(&t.id))
+        .map(|t| t.id.clone())
+        .collect();
+    if purged.is_empty() {
+        return Ok(purged);
+    }
+    
+    let mut changed = purged.clone();
+    for id in &purged {
+        changed.extend(data.todos_referencing(id));
+    }
+    let timer_stopped = data.running_timer().map(|e| purged.contains(&e.todo_id)).unwrap_or(false);
+    if timer_stopped {
+        data.stop_running_timer(Utc::now());
+    }
+    data.keep_time_entries(&purged);
+    data.todos.retain(|t| !purged.contains(&t.id));
+    for id in &purged {
+        data.remove_dependency_refs(id);
+        data.remove_todo_refs(id);
+    }
+    // 留下的子待办提升为顶层待办
+    changed.extend(data.promote_orphans(&purged));
+    state.storage.save(&data)?;
+    
+    // 数据已保存，附件清理失败不影响删除结果
+    for id in &purged {
+        if let Err(e) = state.storage.remove_todo_attachments(id) {
+            eprintln!("{}", e);
+        }
+    }
+    if timer_stopped {
+        update_tray_tooltip(&app, &data);
+    }
+    changed.sort();
+    changed.dedup();
+    emit_data_changed(&app, changed, Vec::new());
+    
+    Ok(purged)
+}
+
+#[tauri::command]
+pub fn create_todo(
+    title: String,
+    details: Option<String>,
+    group_id: String,
+    color_tag: Option<ColorTag>,
+    tags: Option<Vec<String>>,
+    priority: Option<Priority>,
+    start_date: Option<String>,
+    due_date: Option<String>,
+    estimate_minutes: Option<u32>,
+    parent_id: Option<String>,
+    custom_fields: Option<BTreeMap<String, CustomFieldValue>>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<TodoItem, String> {
+    let start_date = start_date.as_deref().map(parse_date_arg).transpose()?.flatten();
+    let due_date = due_date.as_deref().map(parse_date_arg).transpose()?.flatten();
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let parent_id = parent_id.filter(|p| !p.is_empty());
+    if let Some(parent_id) = &parent_id {
+        if !data.todos.iter().any(|t| &t.id == parent_id) {
+            return Err("Parent todo not found".to_string());
+        }
+    }
+    
+    let group = data.groups.iter().find(|g| g.id == group_id);
+    let custom_fields = match custom_fields.filter(|values| !values.is_empty()) {
+        Some(values) => group
+            .ok_or_else(|| "Group not found".to_string())?
+            .validate_custom_fields(values)?,
+        None => BTreeMap::new(),
+    };
+    // 未指定颜色时使用分组的默认颜色
+    let color_tag = color_tag
+        .or_else(|| group.and_then(|g| g.defaults.color_tag))
+        .unwrap_or_default();
+    
+    let order = data.next_todo_order(&group_id, parent_id.as_deref());
+    let mut todo = TodoItem::new(title, group_id, order);
+    todo.parent_id = parent_id;
+    todo.estimate_minutes = estimate_minutes.filter(|m| *m > 0);
+    todo.details = details;
+    todo.color_tag = color_tag;
+    todo.tags = normalize_tags(tags.unwrap_or_default());
+    todo.priority = priority.unwrap_or_default();
+    todo.start_date = start_date;
+    todo.due_date = due_date;
+    todo.custom_fields = custom_fields;
+    
+    data.todos.push(todo.clone());
+    state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo.id.clone()], Vec::new());
+    
+    Ok(todo)
+}
+
+#[tauri::command]
+pub fn update_todo(
+    id: String,
+    title: Option<String>,
+    details: Option<String>,
+    group_id: Option<String>,
+    color_tag: Option<ColorTag>,
+    completed: Option<bool>,
+    hidden: Option<bool>,
+    archived: Option<bool>,
+    tags: Option<Vec<String>>,
+    priority: Option<Priority>,
+    start_date: Option<String>,
+    due_date: Option<String>,
+    estimate_minutes: Option<u32>,
+    parent_id: Option<String>,
+    custom_fields: Option<BTreeMap<String, CustomFieldValue>>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<TodoItem, String> {
+    // 日期、父待办传空字符串表示清除，预估传 0 表示清除
+    let start_date = start_date.as_deref().map(parse_date_arg).transpose()?;
+    let due_date = due_date.as_deref().map(parse_date_arg).transpose()?;
+    let parent_id = parent_id.map(|p| Some(p).filter(|p| !p.is_empty()));
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    if let Some(Some(parent_id)) = &parent_id {
+        if !data.todos.iter().any(|t| &t.id == parent_id) {
+            return Err("Parent todo not found".to_string());
+        }
+        if data.would_create_parent_cycle(&id, parent_id) {
+            return Err("Parent would create a cycle".to_string());
+        }
+    }
+    
+    if let Some(group_id) = &group_id {
+        if !data.groups.iter().any(|g| &g.id == group_id) {
+            return Err("Group not found".to_string());
+        }
+    }
+    
+    // 自定义字段按更新后的分组校验；换组但未传入取值时只保留新分组中仍有效的字段
+    let custom_fields = {
+        let todo = data.todos.iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| "Todo not found".to_string())?;
+        let target_group_id = group_id.as_deref().unwrap_or(&todo.group_id);
+        let group = data.groups.iter().find(|g| g.id == target_group_id);
+        match (custom_fields, group) {
+            (Some(values), Some(group)) => Some(group.validate_custom_fields(values)?),
+            (Some(values), None) if values.is_empty() => Some(BTreeMap::new()),
+            (Some(_), None) => return Err("Group not found".to_string()),
+            (None, group) if group_id.is_some() => {
+                Some(group.map(|g| g.retain_custom_fields(&todo.custom_fields)).unwrap_or_default())
+            }
+            (None, _) => None,
+        }
+    };
+    
+    // 换父待办时排到新父待办的子待办末尾
+    let parent_order = match &parent_id {
+        Some(new_parent_id) => data.todos.iter()
+            .find(|t| t.id == id && &t.parent_id != new_parent_id)
+            .map(|t| data.next_todo_order(&t.group_id, new_parent_id.as_deref())),
+        None => None,
+    };
+    
+    let todo = data.todos.iter_mut()
+        .find(|t| t.id == id)
+        .ok_or_else(|| "Todo not found".to_string())?;
+    let was_completed = todo.completed;
+    
+    if let Some(title) = title {
+        todo.record_change("title", Some(todo.title.clone()), Some(title.clone()));
+        todo.title = title;
+    }
+    if let Some(details) = details {
+        todo.record_change("details", todo.details.clone(), Some(details.clone()));
+        todo.details = Some(details);
+    }
+    if let Some(color_tag) = color_tag {
+        todo.record_change("color_tag", Some(format!("{:?}", todo.color_tag)), Some(format!("{:?}", color_tag)));
+        todo.color_tag = color_tag;
+    }
+    if let Some(completed) = completed {
+        todo.record_change("completed", Some(todo.completed.to_string()), Some(completed.to_string()));
+        todo.set_completed(completed, Utc::now());
+    }
+    if let Some(hidden) = hidden {
+        todo.record_change("hidden", Some(todo.hidden.to_string()), Some(hidden.to_string()));
+        todo.hidden = hidden;
+    }
+    if let Some(tags) = tags {
+        let tags = normalize_tags(tags);
+        todo.record_change("tags", Some(todo.tags.join(", ")), Some(tags.join(", ")));
+        todo.tags = tags;
+    }
+    if let Some(priority) = priority {
+        todo.record_change("priority", Some(format!("{:?}", todo.priority)), Some(format!("{:?}", priority)));
+        todo.priority = priority;
+    }
+    if let Some(start_date) = start_date {
+        todo.record_change("start_date", date_value(&todo.start_date), date_value(&start_date));
+        todo.start_date = start_date;
+    }
+    if let Some(due_date) = due_date {
+        todo.record_change("due_date", date_value(&todo.due_date), date_value(&due_date));
+        todo.due_date = due_date;
+    }
+    if let Some(estimate_minutes) = estimate_minutes {
+        let estimate_minutes = Some(estimate_minutes).filter(|m| *m > 0);
+        todo.record_change(
+            "estimate_minutes",
+            todo.estimate_minutes.map(|m| m.to_string()),
+            estimate_minutes.map(|m| m.to_string()),
+        );
+        todo.estimate_minutes = estimate_minutes;
+    }
+    if let Some(parent_id) = parent_id {
+        todo.record_change("parent_id", todo.parent_id.clone(), parent_id.clone());
+        todo.parent_id = parent_id;
+    }
+    if let Some(order) = parent_order {
+        todo.order = order;
+    }
+    if let Some(custom_fields) = custom_fields {
+        todo.set_custom_fields(custom_fields);
+    }
+    if let Some(archived) = archived {
+        todo.record_change("archived", Some(todo.archived.to_string()), Some(archived.to_string()));
+        todo.archived = archived;
+        if archived {
+            todo.archived_at = Some(Utc::now());
+        } else {
+            todo.archived_at = None;
+        }
+    }
+    
+    todo.updated_at = Utc::now();
+    
+    // 换组时子待办一起移动，放在目标分组末尾
+    let mut changed = vec![id.clone()];
+    if let Some(group_id) = group_id.filter(|g| g != &todo.group_id) {
+        changed = data.move_todos(std::slice::from_ref(&id), &group_id, None)?;
+    }
+    let updated_todo = data.todos.iter()
+        .find(|t| t.id == id)
+        .cloned()
+        .ok_or_else(|| "Todo not found".to_string())?;
+    
+    state.storage.save(&data)?;
+    
+    emit_data_changed(&app, changed, Vec::new());
+    if updated_todo.completed && !was_completed {
+        emit_unblocked(&app, &id, data.unblocked_by(&id));
+    }
+    
+    Ok(updated_todo)
+}
+
+#[tauri::command]
+pub fn delete_todo(id: String, app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    // 删除未完成的依赖同样会解除阻塞
+    if !data.todos.iter().any(|t| t.id == id) {
+        return Err("Todo not found".to_string());
+    }
+    let was_blocking = data.todos.iter().any(|t| t.id == id && !t.completed);
+    let mut changed = vec![id.clone()];
+    changed.extend(data.todos_referencing(&id));
+    let timer_stopped = data.running_timer().map(|e| e.todo_id == id).unwrap_or(false);
+    if timer_stopped {
+        data.stop_running_timer(Utc::now());
+    }
+    data.keep_time_entries(std::slice::from_ref(&id));
+    data.todos.retain(|t| t.id != id);
+    let unblocked: Vec<String> = data.todos.iter()
+        .filter(|t| was_blocking && !t.completed && t.blocked_by.contains(&id))
+        .map(|t| t.id.clone())
+        .collect();
+    data.remove_dependency_refs(&id);
+    data.remove_todo_refs(&id);
+    // 子待办提升为顶层待办，与清除归档一致
+    for todo_id in data.promote_orphans(std::slice::from_ref(&id)) {
+        if !changed.contains(&todo_id) {
+            changed.push(todo_id);
+        }
+    }
+    state.storage.save(&data)?;
+    
+    // 数据已保存，附件清理失败不影响删除结果
+    if let Err(e) = state.storage.remove_todo_attachments(&id) {
+        eprintln!("{}", e);
+    }
+    
+    let unblocked = unblocked.into_iter()
+        .filter(|todo_id| data.todos.iter().any(|t| &t.id == todo_id && !data.is_blocked(t)))
+        .collect();
+    emit_data_changed(&app, changed, Vec::new());
+    emit_unblocked(&app, &id, unblocked);
+    if timer_stopped {
+        update_tray_tooltip(&app, &data);
+    }
+    
+    Ok(())
+}
+
+/// 添加依赖：blocker 完成前 todo 处于阻塞状态；会成环的依赖被拒绝
+#[tauri::command]
+pub fn add_dependency(
+    todo_id: String,
+    blocker_id: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<TodoItem, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    if todo_id == blocker_id {
+        return Err("A todo cannot depend on itself".to_string());
+    }
+    if !data.todos.iter().any(|t| t.id == blocker_id) {
+        return Err("Blocking todo not found".to_string());
+    }
+    if data.would_create_cycle(&todo_id, &blocker_id) {
+        return Err("Dependency would create a cycle".to_string());
+    }
+    
+    let todo = data.todos.iter_mut()
+        .find(|t| t.id == todo_id)
+        .ok_or_else(|| "Todo not found".to_string())?;
+    
+    if !todo.blocked_by.contains(&blocker_id) {
+        todo.record_change("blocked_by", None, Some(blocker_id.clone()));
+        todo.blocked_by.push(blocker_id);
+        todo.updated_at = Utc::now();
+    }
+    let updated_todo = todo.clone();
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
+    
+    Ok(updated_todo)
+}
+
+#[tauri::command]
+pub fn remove_dependency(
+    todo_id: String,
+    blocker_id: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<TodoItem, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let todo = data.todos.iter_mut()
+        .find(|t| t.id == todo_id)
+        .ok_or_else(|| "Todo not found".to_string())?;
+    
+    if todo.blocked_by.contains(&blocker_id) {
+        todo.record_change("blocked_by", Some(blocker_id.clone()), None);
+        todo.blocked_by.retain(|b| b != &blocker_id);
+    }
+    todo.updated_at = Utc::now();
+    let updated_todo = todo.clone();
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
+    
+    Ok(updated_todo)
+}
+
+// 未完成、未归档的待办中按是否阻塞筛选
+fn todos_by_blocked(data: &AppData, group_id: Option<String>, include_subgroups: bool, blocked: bool) -> Vec<TodoItem> {
+    let group_ids = group_id.map(|id| {
+        if include_subgroups { data.group_subtree(&id) } else { vec![id] }
+    });
+    let todos: Vec<TodoItem> = data.todos.iter()
+        .filter(|t| !t.completed && !t.archived)
+        .filter(|t| group_ids.as_ref().map(|ids| ids.contains(&t.group_id)).unwrap_or(true))
+        .filter(|t| data.is_blocked(t) == blocked)
+        .cloned()
+        .collect();
+    data.sort_in_tree(todos)
+}
+
+/// 可以开始的待办：所有依赖均已完成
+#[tauri::command]
+pub fn get_ready_todos(
+    group_id: Option<String>,
+    include_subgroups: Option<bool>,
+    state: State<AppState>,
+) -> Result<Vec<TodoItem>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(todos_by_blocked(&data, group_id, include_subgroups.unwrap_or(false), false))
+}
+
+/// 仍在等待依赖完成的待办
+#[tauri::command]
+pub fn get_blocked_todos(
+    group_id: Option<String>,
+    include_subgroups: Option<bool>,
+    state: State<AppState>,
+) -> Result<Vec<TodoItem>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(todos_by_blocked(&data, group_id, include_subgroups.unwrap_or(false), true))
+}
+
+/// 按给定顺序重排同一分组、同一父待办下的待办
+#[tauri::command]
+pub fn reorder_todos(todo_ids: Vec<String>, app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let scopes: Vec<(&str, Option<&str>)> = todo_ids.iter()
+        .filter_map(|id| data.todos.iter().find(|t| &t.id == id))
+        .map(|t| (t.group_id.as_str(), t.parent_id.as_deref()))
+        .collect();
+    if scopes.windows(2).any(|w| w[0] != w[1]) {
+        return Err("Todos must share the same group and parent".to_string());
+    }
+    
+    let snapshot = order_snapshot(&data);
+    for (index, id) in todo_ids.iter().enumerate() {
+        if let Some(todo) = data.todos.iter_mut().find(|t| &t.id == id) {
+            todo.order = index as f64;
+        }
+    }
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, reordered_since(&data, &snapshot), Vec::new());
+    Ok(())
+}
+
+/// 把待办放到兄弟待办 after_id 之后（不传时放到最前），通常只改动这一个待办
+#[tauri::command]
+pub fn reposition_todo(id: String, after_id: Option<String>, app: AppHandle, state: State<AppState>) -> Result<TodoItem, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let snapshot = order_snapshot(&data);
+    data.place_todo_after(&id, after_id.as_deref().filter(|a| !a.is_empty()))?;
+    let todo = data.todos.iter()
+        .find(|t| t.id == id)
+        .cloned()
+        .ok_or_else(|| "Todo not found".to_string())?;
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, reordered_since(&data, &snapshot), Vec::new());
+    Ok(todo)
+}
+
+/// 把待办连同子待办移到目标分组的 position 处（默认末尾），返回被移动的待办
+#[tauri::command]
+pub fn move_todos(
+    ids: Vec<String>,
+    target_group_id: String,
+    position: Option<usize>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Vec<TodoItem>, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let snapshot = order_snapshot(&data);
+    let moved = data.move_todos(&ids, &target_group_id, position)?;
+    let todos = data.sort_in_tree(data.todos.iter()
+        .filter(|t| moved.contains(&t.id))
+        .cloned()
+        .collect());
+    
+    state.storage.save(&data)?;
+    let mut changed = moved;
+    for id in reordered_since(&data, &snapshot) {
+        if !changed.contains(&id) {
+            changed.push(id);
+        }
+    }
+    emit_data_changed(&app, changed, Vec::new());
+    
+    Ok(todos)
+}
+
+/// 待办的变更记录，按时间先后排列
+#[tauri::command]
+pub fn get_todo_history(id: String, state: State<AppState>) -> Result<Vec<HistoryEntry>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let todo = data.todos.iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| "Todo not found".to_string())?;
+    
+    Ok(todo.history.clone())
+}
+
+fn push_attachment(data: &mut AppData, todo_id: &str, attachment: Attachment) -> Result<(), String> {
+    let todo = data.todos.iter_mut()
+        .find(|t| t.id == todo_id)
+        .ok_or_else(|| "Todo not found".to_string())?;
+    
+    todo.record_change("attachment", None, Some(attachment.label()));
+    todo.attachments.push(attachment);
+    todo.updated_at = Utc::now();
+    
+    Ok(())
+}
+
+/// 添加本地文件附件；copy 为 true 时复制到应用数据目录，否则只保存路径
+#[tauri::command]
+pub fn add_file_attachment(
+    todo_id: String,
+    path: String,
+    copy: bool,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Attachment, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    if !data.todos.iter().any(|t| t.id == todo_id) {
+        return Err("Todo not found".to_string());
+    }
+    let source = std::path::Path::new(&path);
+    if !source.is_file() {
+        return Err("File not found".to_string());
+    }
+    let name = source.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    
+    let stored_path = if copy {
+        state.storage.copy_attachment(&path, &todo_id)?.to_string_lossy().to_string()
+    } else {
+        path
+    };
+    let attachment = Attachment::new(AttachmentKind::File { path: stored_path, name, copied: copy });
+    push_attachment(&mut data, &todo_id, attachment.clone())?;
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
+    
+    Ok(attachment)
+}
+
+#[tauri::command]
+pub fn add_url_attachment(
+    todo_id: String,
+    url: String,
+    title: Option<String>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Attachment, String> {
+    let url = url.trim().to_string();
+    if !["http://", "https://", "mailto:"].iter().any(|p| url.starts_with(p)) {
+        return Err("Unsupported URL".to_string());
+    }
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let title = title.filter(|t| !t.trim().is_empty());
+    let attachment = Attachment::new(AttachmentKind::Url { url, title });
+    push_attachment(&mut data, &todo_id, attachment.clone())?;
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
+    
+    Ok(attachment)
+}
+
+/// 引用另一个待办
+#[tauri::command]
+pub fn add_todo_reference(
+    todo_id: String,
+    target_id: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Attachment, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    if todo_id == target_id {
+        return Err("A todo cannot reference itself".to_string());
+    }
+    if !data.todos.iter().any(|t| t.id == target_id) {
+        return Err("Referenced todo not found".to_string());
+    }
+    
+    let attachment = Attachment::new(AttachmentKind::TodoRef { todo_id: target_id });
+    push_attachment(&mut data, &todo_id, attachment.clone())?;
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
+    
+    Ok(attachment)
+}
+
+/// 删除附件；复制进来的文件一并删除
+#[tauri::command]
+pub fn remove_attachment(
+    todo_id: String,
+    attachment_id: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let todo = data.todos.iter_mut()
+        .find(|t| t.id == todo_id)
+        .ok_or_else(|| "Todo not found".to_string())?;
+    
+    let attachment = todo.attachments.iter()
+        .find(|a| a.id == attachment_id)
+        .cloned()
+        .ok_or_else(|| "Attachment not found".to_string())?;
+    
+    todo.record_change("attachment", Some(attachment.label()), None);
+    todo.attachments.retain(|a| a.id != attachment_id);
+    todo.updated_at = Utc::now();
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
+    
+    if let Some(path) = attachment.copied_path() {
+        state.storage.remove_attachment_file(path)?;
+    }
+    
+    Ok(())
+}
+
+// 用系统默认程序打开本地文件；只在后端调用，shell open 白名单仍只允许链接
+fn open_local_file(path: &str) -> Result<(), String> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    
+    std::process::Command::new(program)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open attachment: {}", e))
+}
+
+/// 打开附件：文件只打开该附件记录的路径，链接受 shell open 白名单限制；待办引用通过 "open-todo" 事件交给前端跳转
+#[tauri::command]
+pub fn open_attachment(
+    todo_id: String,
+    attachment_id: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let attachment = data.todos.iter()
+        .find(|t| t.id == todo_id)
+        .and_then(|t| t.attachments.iter().find(|a| a.id == attachment_id))
+        .cloned()
+        .ok_or_else(|| "Attachment not found".to_string())?;
+    drop(data);
+    
+    match attachment.kind {
+        AttachmentKind::File { path, .. } => {
+            let file = std::path::Path::new(&path);
+            if !file.is_absolute() || !file.is_file() {
+                return Err("File not found".to_string());
+            }
+            open_local_file(&path)
+        }
+        AttachmentKind::Url { url, .. } => {
+            tauri::api::shell::open(&app.shell_scope(), url, None)
+                .map_err(|e| format!("Failed to open attachment: {}", e))
+        }
+        AttachmentKind::TodoRef { todo_id } => {
+            app.emit_all("open-todo", todo_id)
+                .map_err(|e| format!("Failed to open todo: {}", e))
+        }
+    }
+}
+
+fn copy_attachment_files(storage: &Storage, copies: &mut [TodoItem]) -> Result<(), String> {
+    for copy in copies.iter_mut() {
+        for attachment in copy.attachments.iter_mut() {
+            if let AttachmentKind::File { path, copied: true, .. } = &mut attachment.kind {
+                *path = storage.copy_attachment(path, &copy.id)?.to_string_lossy().to_string();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 复制待办及（可选）子待办，副本放在原待办之后
+#[tauri::command]
+pub fn duplicate_todo(
+    id: String,
+    options: Option<DuplicateOptions>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Vec<TodoItem>, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let mut copies = templates::duplicate_copies(&data, &id, &options.unwrap_or_default())?;
+    
+    // 复制进来的附件文件也要复制一份，避免删除原待办时被一并删除；
+    // 文件复制完再写入数据，失败时清理已复制的文件
+    if let Err(e) = copy_attachment_files(&state.storage, &mut copies) {
+        for copy in &copies {
+            if let Err(e) = state.storage.remove_todo_attachments(&copy.id) {
+                eprintln!("{}", e);
+            }
+        }
+        return Err(e);
+    }
+    
+    let snapshot = order_snapshot(&data);
+    let copies = templates::insert_duplicates(&mut data, &id, copies)?;
+    
+    state.storage.save(&data)?;
+    emit_data_changed(&app, reordered_since(&data, &snapshot), Vec::new());
+    
+    Ok(copies)
+}
+
+#[tauri::command]
+pub fn list_templates(state: State<AppState>) -> Result<Vec<TodoTemplate>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(data.templates.clone())
+}
+
+/// 由待办及其子待办创建模板
+#[tauri::command]
+pub fn create_template_from_todo(
+    todo_id: String,
+    name: String,
+    state: State<AppState>,
+) -> Result<TodoTemplate, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let template = templates::from_subtree(&data, &todo_id, name)?;
+    templates::validate(&template)?;
+    data.templates.push(template.clone());
+    
+    state.storage.save(&data)?;
+    
+    Ok(template)
+}
+
+/// 保存编辑后的模板（按 id 覆盖）
+#[tauri::command]
+pub fn update_template(template: TodoTemplate, state: State<AppState>) -> Result<TodoTemplate, String> {
+    templates::validate(&template)?;
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let existing = data.templates.iter_mut()
+        .find(|t| t.id == template.id)
+        .ok_or_else(|| "Template not found".to_string())?;
+    
+    existing.name = template.name;
+    existing.items = template.items;
+    existing.updated_at = Utc::now();
+    let updated_template = existing.clone();
+    
+    state.storage.save(&data)?;
+    
+    Ok(updated_template)
+}
+
+#[tauri::command]
+pub fn delete_template(id: String, state: State<AppState>) -> Result<(), String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    data.templates.retain(|t| t.id != id);
+    state.storage.save(&data)?;
+    
+    Ok(())
+}
+
+/// 以 base_date 为基准在分组中实例化模板，返回新建的待办
+#[tauri::command]
+pub fn instantiate_template(
+    template_id: String,
+    group_id: String,
+    base_date: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Vec<TodoItem>, String> {
+    let base = parse_date_arg(&base_date)?.unwrap_or_else(Utc::now);
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let template = data.templates.iter()
+        .find(|t| t.id == template_id)
+        .cloned()
+        .ok_or_else(|| "Template not found".to_string())?;
+    
+    let todos = templates::instantiate(&mut data, &template, &group_id, base)?;
+    state.storage.save(&data)?;
+    emit_data_changed(&app, todos.iter().map(|t| t.id.clone()).collect(), Vec::new());
+    
+    Ok(todos)
+}
+
+/// 开始计时；已有计时在运行时先将其结束
+#[tauri::command]
+pub fn start_timer(
+    todo_id: String,
+    note: Option<String>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<TimeEntry, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    if !data.todos.iter().any(|t| t.id == todo_id) {
+        return Err("Todo not found".to_string());
+    }
+    
+    let now = Utc::now();
+    data.stop_running_timer(now);
+    let entry = TimeEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        todo_id,
+        start: now,
+        end: None,
+        note: note.filter(|n| !n.trim().is_empty()),
+        title: None,
+        group_id: None,
+    };
+    data.time_entries.push(entry.clone());
+    
+    state.storage.save(&data)?;
+    update_tray_tooltip(&app, &data);
+    
+    Ok(entry)
+}
+
+/// 结束正在运行的计时；note 不为空时覆盖原备注
+#[tauri::command]
+pub fn stop_timer(
+    note: Option<String>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Option<TimeEntry>, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let Some(mut entry) = data.stop_running_timer(Utc::now()) else {
+        return Ok(None);
+    };
+    if let Some(note) = note.filter(|n| !n.trim().is_empty()) {
+        if let Some(existing) = data.time_entries.iter_mut().find(|e| e.id == entry.id) {
+            existing.note = Some(note.clone());
+        }
+        entry.note = Some(note);
+    }
+    
+    state.storage.save(&data)?;
+    update_tray_tooltip(&app, &data);
+    
+    Ok(Some(entry))
+}
+
+#[tauri::command]
+pub fn get_running_timer(state: State<AppState>) -> Result<Option<TimeEntry>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(data.running_timer().cloned())
+}
+
+/// 按开始时间排列的计时记录，可按待办和时间范围筛选
+#[tauri::command]
+pub fn list_time_entries(
+    todo_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    state: State<AppState>,
+) -> Result<Vec<TimeEntry>, String> {
+    let from = from.as_deref().map(parse_date_arg).transpose()?.flatten();
+    let to = to.as_deref().map(parse_date_arg).transpose()?.flatten();
+    
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let now = Utc::now();
+    let mut entries: Vec<TimeEntry> = data.time_entries.iter()
+        .filter(|e| todo_id.as_ref().map(|id| &e.todo_id == id).unwrap_or(true))
+        .filter(|e| from.map(|f| e.end.unwrap_or(now) > f).unwrap_or(true))
+        .filter(|e| to.map(|t| e.start < t).unwrap_or(true))
+        .cloned()
+        .collect();
+    entries.sort_by_key(|e| e.start);
+    
+    Ok(entries)
+}
+
+/// 按待办、分组和天汇总计时（秒）
+#[tauri::command]
+pub fn get_time_totals(
+    from: Option<String>,
+    to: Option<String>,
+    state: State<AppState>,
+) -> Result<TimeTotals, String> {
+    let from = from.as_deref().map(parse_date_arg).transpose()?.flatten();
+    let to = to.as_deref().map(parse_date_arg).transpose()?.flatten();
+    
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    Ok(data.time_totals(from, to))
+}
+
+/// 待办及其子待办的预估、剩余和已记录工作量
+#[tauri::command]
+pub fn get_effort_rollup(todo_id: String, state: State<AppState>) -> Result<EffortRollup, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    data.effort_rollup(&todo_id)
+        .ok_or_else(|| "Todo not found".to_string())
+}
+
+/// 分组内所有待办的预估、已记录和剩余工作量
+#[tauri::command]
+pub fn get_group_effort_summary(
+    group_id: String,
+    include_subgroups: Option<bool>,
+    state: State<AppState>,
+) -> Result<GroupEffortSummary, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    if !data.groups.iter().any(|g| g.id == group_id) {
+        return Err("Group not found".to_string());
+    }
+    
+    Ok(data.group_effort_summary(&group_id, include_subgroups.unwrap_or(false)))
+}
+
+/// 完成情况统计：按天或按周的新建/完成数、平均耗时、分组与颜色分布以及连续完成天数
+#[tauri::command]
+pub fn get_stats(range: Option<StatsRange>, state: State<AppState>) -> Result<Stats, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    Ok(stats::compute(&data, &range.unwrap_or_default()))
+}
+
+/// 开始一个专注番茄；已有番茄在进行时先中断它
+#[tauri::command]
+pub fn start_pomodoro(
+    todo_id: Option<String>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<PomodoroStatus, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    let mut engine = state.pomodoro.lock()
+        .map_err(|e| format!("Failed to lock pomodoro: {}", e))?;
+    
+    if let Some(todo_id) = &todo_id {
+        if !data.todos.iter().any(|t| &t.id == todo_id) {
+            return Err("Todo not found".to_string());
+        }
+    }
+    
+    let now = Utc::now();
+    if let Some(interrupted) = engine.stop() {
+        pomodoro::record_focus_session(&mut data, &interrupted, now, false);
+    }
+    let status = engine.start_work(todo_id, &data.settings.pomodoro, now);
+    drop(engine);
+    
+    state.storage.save(&data)?;
+    pomodoro::emit_status(&app, Some(status.clone()));
+    
+    Ok(status)
+}
+
+/// 停止当前的番茄或休息；未完成的专注记为中断
+#[tauri::command]
+pub fn stop_pomodoro(app: AppHandle, state: State<AppState>) -> Result<Option<PomodoroStatus>, String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    let mut engine = state.pomodoro.lock()
+        .map_err(|e| format!("Failed to lock pomodoro: {}", e))?;
+    
+    let stopped = engine.stop();
+    drop(engine);
+    
+    if let Some(status) = &stopped {
+        pomodoro::record_focus_session(&mut data, status, Utc::now(), false);
+        state.storage.save(&data)?;
+    }
+    pomodoro::emit_status(&app, None);
+    
+    Ok(stopped)
+}
+
+#[tauri::command]
+pub fn get_pomodoro_status(state: State<AppState>) -> Result<Option<PomodoroStatus>, String> {
+    let engine = state.pomodoro.lock()
+        .map_err(|e| format!("Failed to lock pomodoro: {}", e))?;
+    Ok(engine.status())
+}
+
+/// 专注统计：完成/中断的番茄数及按待办、按天的专注时长（秒）
 #[tauri::command]
-pub fn get_all_data(state: State<AppState>) -> Result<AppData, String> {
+pub fn get_focus_stats(
+    from: Option<String>,
+    to: Option<String>,
+    state: State<AppState>,
+) -> Result<FocusStats, String> {
+    let from = from.as_deref().map(parse_date_arg).transpose()?.flatten();
+    let to = to.as_deref().map(parse_date_arg).transpose()?.flatten();
+    
     let data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
-    Ok(data.clone())
+    
+    Ok(data.focus_stats(from, to))
 }
 
+/// 按关键字（标题、详情、标签、自定义字段）和筛选条件查找待办；关键字为空时返回全部匹配项
 #[tauri::command]
-pub fn create_todo(
-    title: String,
-    details: Option<String>,
-    group_id: String,
-    color_tag: ColorTag,
+pub fn search_todos(
+    query: Option<String>,
+    filter: Option<TodoFilter>,
+    sort_by: Option<Vec<SortKey>>,
     state: State<AppState>,
-) -> Result<TodoItem, String> {
-    let mut data = state.data.lock()
+) -> Result<Vec<TodoItem>, String> {
+    let data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    let todo = TodoItem {
-        id: uuid::Uuid::new_v4().to_string(),
-        title,
-        details,
-        group_id,
-        color_tag,
-        completed: false,
-        hidden: false,
-        archived: false,
-        archived_at: None,
-        order: data.todos.len() as i32,
-        parent_id: None,
-        time_nodes: Vec::new(),
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
-    data.todos.push(todo.clone());
-    state.storage.save(&data)?;
-    
-    Ok(todo)
+    let filter = data.expand_group_filter(&filter.unwrap_or_default());
+    let query = query.map(|q| q.trim().to_lowercase()).unwrap_or_default();
+    
+    let todos: Vec<TodoItem> = data.todos.iter()
+        .filter(|t| filter.matches(t))
+        .filter(|t| {
+            query.is_empty()
+                || t.title.to_lowercase().contains(&query)
+                || t.details.as_deref().map(|d| d.to_lowercase().contains(&query)).unwrap_or(false)
+                || t.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
+                || t.custom_fields.values().any(|v| v.display().to_lowercase().contains(&query))
+        })
+        .cloned()
+        .collect();
+    let sort_by = sort_by.unwrap_or_else(|| vec![SortKey::Order]);
+    
+    Ok(data.sort_todos_by(todos, &sort_by))
 }
 
+/// 按排序键对分组内的待办排序，默认依次按截止日期、优先级、原顺序；
+/// persist 为 true 时把结果按父待办分别写回 order
 #[tauri::command]
-pub fn update_todo(
-    id: String,
-    title: Option<String>,
-    details: Option<String>,
-    group_id: Option<String>,
-    color_tag: Option<ColorTag>,
-    completed: Option<bool>,
-    hidden: Option<bool>,
-    archived: Option<bool>,
+pub fn sort_todos(
+    group_id: String,
+    sort_by: Option<Vec<SortKey>>,
+    persist: Option<bool>,
+    app: AppHandle,
     state: State<AppState>,
-) -> Result<TodoItem, String> {
+) -> Result<Vec<TodoItem>, String> {
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    let todo = data.todos.iter_mut()
-        .find(|t| t.id == id)
-        .ok_or_else(|| "Todo not found".to_string())?;
-    
-    if let Some(title) = title {
-        todo.title = title;
-    }
-    if let Some(details) = details {
-        todo.details = Some(details);
-    }
-    if let Some(group_id) = group_id {
-        todo.group_id = group_id;
-    }
-    if let Some(color_tag) = color_tag {
-        todo.color_tag = color_tag;
-    }
-    if let Some(completed) = completed {
-        todo.completed = completed;
-    }
-    if let Some(hidden) = hidden {
-        todo.hidden = hidden;
-    }
-    if let Some(archived) = archived {
-        todo.archived = archived;
-        if archived {
-            todo.archived_at = Some(Utc::now());
-        } else {
-            todo.archived_at = None;
+    let sort_by = sort_by.unwrap_or_else(|| vec![SortKey::DueDate, SortKey::Priority, SortKey::Order]);
+    let todos: Vec<TodoItem> = data.todos.iter()
+        .filter(|t| t.group_id == group_id)
+        .cloned()
+        .collect();
+    let mut todos = data.sort_todos_by(todos, &sort_by);
+    
+    if persist.unwrap_or(false) {
+        // order 只在兄弟之间有意义，每个父待办下分别从 0 编号
+        let snapshot = order_snapshot(&data);
+        let mut next_orders: BTreeMap<Option<String>, f64> = BTreeMap::new();
+        for sorted in todos.iter_mut() {
+            let next = next_orders.entry(sorted.parent_id.clone()).or_insert(0.0);
+            sorted.order = *next;
+            *next += 1.0;
+            if let Some(todo) = data.todos.iter_mut().find(|t| t.id == sorted.id) {
+                todo.order = sorted.order;
+            }
         }
+        state.storage.save(&data)?;
+        emit_data_changed(&app, reordered_since(&data, &snapshot), Vec::new());
     }
     
-    todo.updated_at = Utc::now();
-    let updated_todo = todo.clone();
+    Ok(todos)
+}
+
+#[tauri::command]
+pub fn list_tags(state: State<AppState>) -> Result<Vec<TagCount>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    Ok(data.tag_counts())
+}
+
+#[tauri::command]
+pub fn rename_tag(
+    old_name: String,
+    new_name: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<usize, String> {
+    let new_name = normalize_tags(vec![new_name]).pop()
+        .ok_or_else(|| "Tag name cannot be empty".to_string())?;
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
     
+    let affected = data.replace_tags(&[old_name], Some(&new_name));
     state.storage.save(&data)?;
+    let count = affected.len();
+    emit_data_changed(&app, affected, Vec::new());
     
-    Ok(updated_todo)
+    Ok(count)
 }
 
 #[tauri::command]
-pub fn delete_todo(id: String, state: State<AppState>) -> Result<(), String> {
+pub fn merge_tags(
+    sources: Vec<String>,
+    target: String,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<usize, String> {
+    let target = normalize_tags(vec![target]).pop()
+        .ok_or_else(|| "Tag name cannot be empty".to_string())?;
+    
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    data.todos.retain(|t| t.id != id);
+    let affected = data.replace_tags(&sources, Some(&target));
     state.storage.save(&data)?;
+    let count = affected.len();
+    emit_data_changed(&app, affected, Vec::new());
     
-    Ok(())
+    Ok(count)
 }
 
 #[tauri::command]
-pub fn reorder_todos(todo_ids: Vec<String>, state: State<AppState>) -> Result<(), String> {
+pub fn delete_tag(name: String, app: AppHandle, state: State<AppState>) -> Result<usize, String> {
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    for (index, id) in todo_ids.iter().enumerate() {
-        if let Some(todo) = data.todos.iter_mut().find(|t| &t.id == id) {
-            todo.order = index as i32;
-        }
-    }
-    
+    let affected = data.replace_tags(&[name], None);
     state.storage.save(&data)?;
-    Ok(())
+    let count = affected.len();
+    emit_data_changed(&app, affected, Vec::new());
+    
+    Ok(count)
 }
 
 #[tauri::command]
@@ -140,13 +1417,26 @@ pub fn add_time_node(
     todo_id: String,
     date_time: String,
     description: Option<String>,
-    reminder_enabled: bool,
-    reminder_minutes_before: i32,
+    reminder_enabled: Option<bool>,
+    reminder_minutes_before: Option<i32>,
+    app: AppHandle,
     state: State<AppState>,
 ) -> Result<TimeNode, String> {
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
+    let group_id = data.todos.iter()
+        .find(|t| t.id == todo_id)
+        .map(|t| t.group_id.clone())
+        .ok_or_else(|| "Todo not found".to_string())?;
+    
+    // 未指定提醒时使用待办所在分组的默认提醒
+    let default_reminder = data.groups.iter()
+        .find(|g| g.id == group_id)
+        .and_then(|g| g.defaults.reminder_minutes_before);
+    let reminder_enabled = reminder_enabled.unwrap_or(default_reminder.is_some());
+    let reminder_minutes_before = reminder_minutes_before.or(default_reminder).unwrap_or(0);
+    
     let todo = data.todos.iter_mut()
         .find(|t| t.id == todo_id)
         .ok_or_else(|| "Todo not found".to_string())?;
@@ -163,10 +1453,12 @@ pub fn add_time_node(
         created_at: Utc::now(),
     };
     
+    todo.record_change("time_node", None, Some(time_node_value(&time_node)));
     todo.time_nodes.push(time_node.clone());
     todo.updated_at = Utc::now();
     
     state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
     
     Ok(time_node)
 }
@@ -178,6 +1470,7 @@ pub fn update_time_node(
     description: Option<String>,
     reminder_enabled: bool,
     reminder_minutes_before: i32,
+    app: AppHandle,
     state: State<AppState>,
 ) -> Result<TimeNode, String> {
     let mut data = state.data.lock()
@@ -196,15 +1489,19 @@ pub fn update_time_node(
     let parsed_date = chrono::DateTime::parse_from_rfc3339(&date_time)
         .map_err(|e| format!("Invalid date format: {}", e))?;
     
+    let old_value = time_node_value(time_node);
     time_node.date_time = parsed_date.with_timezone(&Utc);
     time_node.description = description;
     time_node.reminder_enabled = reminder_enabled;
     time_node.reminder_minutes_before = reminder_minutes_before;
     
     let updated_node = time_node.clone();
+    todo.record_change("time_node", Some(old_value), Some(time_node_value(&updated_node)));
     todo.updated_at = Utc::now();
+    let todo_id = todo.id.clone();
     
     state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
     
     Ok(updated_node)
 }
@@ -212,6 +1509,7 @@ pub fn update_time_node(
 #[tauri::command]
 pub fn delete_time_node(
     id: String,
+    app: AppHandle,
     state: State<AppState>,
 ) -> Result<(), String> {
     let mut data = state.data.lock()
@@ -223,29 +1521,41 @@ pub fn delete_time_node(
         .ok_or_else(|| "Time node not found".to_string())?;
     
     // 删除时间节点
+    let old_value = todo.time_nodes.iter().find(|tn| tn.id == id).map(time_node_value);
+    todo.record_change("time_node", old_value, None);
     todo.time_nodes.retain(|tn| tn.id != id);
     todo.updated_at = Utc::now();
+    let todo_id = todo.id.clone();
     
     state.storage.save(&data)?;
+    emit_data_changed(&app, vec![todo_id], Vec::new());
     
     Ok(())
 }
 
 #[tauri::command]
-pub fn create_group(name: String, state: State<AppState>) -> Result<TodoGroup, String> {
+pub fn create_group(
+    name: String,
+    parent_group_id: Option<String>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<TodoGroup, String> {
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    let group = TodoGroup {
-        id: uuid::Uuid::new_v4().to_string(),
-        name,
-        order: data.groups.len() as i32,
-        is_default: false,
-        created_at: Utc::now(),
-    };
+    let parent_group_id = parent_group_id.filter(|p| !p.is_empty());
+    if let Some(parent_group_id) = &parent_group_id {
+        if !data.groups.iter().any(|g| &g.id == parent_group_id) {
+            return Err("Parent group not found".to_string());
+        }
+    }
+    
+    let order = data.next_group_order(parent_group_id.as_deref());
+    let group = TodoGroup::new(name, parent_group_id, order);
     
     data.groups.push(group.clone());
     state.storage.save(&data)?;
+    emit_data_changed(&app, Vec::new(), vec![group.id.clone()]);
     
     Ok(group)
 }
@@ -254,69 +1564,241 @@ pub fn create_group(name: String, state: State<AppState>) -> Result<TodoGroup, S
 pub fn update_group(
     id: String,
     name: String,
+    parent_group_id: Option<String>,
+    color: Option<String>,
+    icon: Option<String>,
+    description: Option<String>,
+    defaults: Option<GroupDefaults>,
+    app: AppHandle,
     state: State<AppState>,
 ) -> Result<TodoGroup, String> {
+    // 上级分组、颜色、图标、描述传空字符串表示清除
+    let parent_group_id = parent_group_id.map(|p| Some(p).filter(|p| !p.is_empty()));
+    let clearable = |value: Option<String>| value.map(|v| Some(v.trim().to_string()).filter(|v| !v.is_empty()));
+    let color = clearable(color);
+    let icon = clearable(icon);
+    let description = clearable(description);
+    
+    if let Some(Some(color)) = &color {
+        if !is_hex_color(color) {
+            return Err(format!("Invalid color: {}", color));
+        }
+    }
+    if let Some(minutes) = defaults.as_ref().and_then(|d| d.reminder_minutes_before) {
+        if minutes < 0 {
+            return Err("Reminder minutes cannot be negative".to_string());
+        }
+    }
+    
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
+    if let Some(Some(parent_group_id)) = &parent_group_id {
+        if !data.groups.iter().any(|g| &g.id == parent_group_id) {
+            return Err("Parent group not found".to_string());
+        }
+        // 不能移到自己或自己的子孙分组下
+        if data.group_subtree(&id).contains(parent_group_id) {
+            return Err("Parent group would create a cycle".to_string());
+        }
+    }
+    // 换上级时排到新上级的末尾
+    let order = parent_group_id.as_ref().map(|p| data.next_group_order(p.as_deref()));
+    
     let group = data.groups.iter_mut()
         .find(|g| g.id == id)
         .ok_or_else(|| "Group not found".to_string())?;
     
-    if group.is_default {
-        return Err("Cannot modify default group".to_string());
+    group.name = name;
+    if let (Some(parent_group_id), Some(order)) = (parent_group_id, order) {
+        if group.parent_group_id != parent_group_id {
+            group.parent_group_id = parent_group_id;
+            group.order = order;
+        }
+    }
+    if let Some(color) = color {
+        group.color = color;
     }
+    if let Some(icon) = icon {
+        group.icon = icon;
+    }
+    if let Some(description) = description {
+        group.description = description;
+    }
+    if let Some(defaults) = defaults {
+        group.defaults = defaults;
+    }
+    let updated_group = group.clone();
     
-    group.name = name;
+    state.storage.save(&data)?;
+    emit_data_changed(&app, Vec::new(), vec![id]);
+    
+    Ok(updated_group)
+}
+
+/// 设置分组的自定义字段定义；组内待办中不再符合定义的取值会被移除
+#[tauri::command]
+pub fn set_group_custom_fields(
+    group_id: String,
+    fields: Vec<CustomFieldDef>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<TodoGroup, String> {
+    let fields = normalize_custom_field_defs(fields)?;
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let group = data.groups.iter_mut()
+        .find(|g| g.id == group_id)
+        .ok_or_else(|| "Group not found".to_string())?;
+    group.custom_fields = fields;
     let updated_group = group.clone();
     
+    let pruned: Vec<String> = data.todos.iter()
+        .filter(|t| t.group_id == group_id && !t.custom_fields.is_empty())
+        .map(|t| t.id.clone())
+        .collect();
+    data.prune_custom_fields(&group_id);
     state.storage.save(&data)?;
+    emit_data_changed(&app, pruned, vec![group_id]);
     
     Ok(updated_group)
 }
 
+/// 设为默认分组：导入时未指定分组的待办和被删分组的待办默认放入该分组，默认分组不能删除
+#[tauri::command]
+pub fn set_default_group(id: String, app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let previous = data.default_group_id();
+    data.set_default_group(&id)?;
+    state.storage.save(&data)?;
+    emit_data_changed(&app, Vec::new(), vec![previous, id]);
+    
+    Ok(())
+}
+
+/// 删除分组及其子分组；move_todos 为 true 时待办移到 move_to（未指定时为默认分组），否则一并删除
 #[tauri::command]
 pub fn delete_group(
     id: String,
-    move_to_personal: bool,
+    move_todos: bool,
+    move_to: Option<String>,
+    app: AppHandle,
     state: State<AppState>,
 ) -> Result<(), String> {
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    let group = data.groups.iter()
-        .find(|g| g.id == id)
-        .ok_or_else(|| "Group not found".to_string())?;
+    if !data.groups.iter().any(|g| g.id == id) {
+        return Err("Group not found".to_string());
+    }
     
-    if group.is_default {
+    // 子分组随上级一起删除
+    let group_ids = data.group_subtree(&id);
+    if data.groups.iter().any(|g| g.is_default && group_ids.contains(&g.id)) {
         return Err("Cannot delete default group".to_string());
     }
     
-    // 如果需要移动待办到personal分组
-    if move_to_personal {
-        for todo in data.todos.iter_mut() {
-            if todo.group_id == id {
-                todo.group_id = "personal".to_string();
+    let mut changed: Vec<String> = data.todos.iter()
+        .filter(|t| group_ids.contains(&t.group_id))
+        .map(|t| t.id.clone())
+        .collect();
+    
+    let mut timer_stopped = false;
+    let mut deleted: Vec<String> = Vec::new();
+    
+    // 如果需要移动待办到其他分组
+    if move_todos {
+        let target = move_to.filter(|m| !m.is_empty()).unwrap_or_else(|| data.default_group_id());
+        if !data.groups.iter().any(|g| g.id == target) {
+            return Err("Target group not found".to_string());
+        }
+        if group_ids.contains(&target) {
+            return Err("Cannot move todos into a deleted group".to_string());
+        }
+        // 与 move_todos 一样整棵子树移动，并放到目标分组末尾
+        let todos = data.sort_in_tree(data.todos.iter()
+            .filter(|t| group_ids.contains(&t.group_id))
+            .cloned()
+            .collect());
+        let ids: Vec<String> = todos.into_iter().map(|t| t.id).collect();
+        let snapshot = order_snapshot(&data);
+        data.move_todos(&ids, &target, None)?;
+        for id in reordered_since(&data, &snapshot) {
+            if !changed.contains(&id) {
+                changed.push(id);
             }
         }
     } else {
-        // 否则删除该分组的所有待办
-        data.todos.retain(|t| t.group_id != id);
+        // 否则删除这些分组的所有待办
+        deleted = data.todos.iter()
+            .filter(|t| group_ids.contains(&t.group_id))
+            .map(|t| t.id.clone())
+            .collect();
+        timer_stopped = data.running_timer().map(|e| deleted.contains(&e.todo_id)).unwrap_or(false);
+        if timer_stopped {
+            data.stop_running_timer(Utc::now());
+        }
+        data.keep_time_entries(&deleted);
+        data.todos.retain(|t| !group_ids.contains(&t.group_id));
+        for todo_id in &deleted {
+            for other in data.todos_referencing(todo_id) {
+                if !changed.contains(&other) {
+                    changed.push(other);
+                }
+            }
+            data.remove_dependency_refs(todo_id);
+            data.remove_todo_refs(todo_id);
+        }
+        for todo_id in data.promote_orphans(&deleted) {
+            if !changed.contains(&todo_id) {
+                changed.push(todo_id);
+            }
+        }
     }
     
     // 删除分组
-    data.groups.retain(|g| g.id != id);
+    data.groups.retain(|g| !group_ids.contains(&g.id));
     
     state.storage.save(&data)?;
     
+    // 数据已保存，附件清理失败不影响删除结果
+    for todo_id in &deleted {
+        if let Err(e) = state.storage.remove_todo_attachments(todo_id) {
+            eprintln!("{}", e);
+        }
+    }
+    emit_data_changed(&app, changed, group_ids);
+    if timer_stopped {
+        update_tray_tooltip(&app, &data);
+    }
+    
     Ok(())
 }
 
+/// 调整同一上级下分组的顺序；parent_group_id 为空时调整顶层分组
 #[tauri::command]
-pub fn reorder_groups(group_ids: Vec<String>, state: State<AppState>) -> Result<(), String> {
+pub fn reorder_groups(
+    group_ids: Vec<String>,
+    parent_group_id: Option<String>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let parent_group_id = parent_group_id.filter(|p| !p.is_empty());
+    
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
+    let in_parent = |id: &String| {
+        data.groups.iter().any(|g| &g.id == id && g.parent_group_id == parent_group_id)
+    };
+    if !group_ids.iter().all(in_parent) {
+        return Err("Group is not in this parent".to_string());
+    }
+    
     for (index, id) in group_ids.iter().enumerate() {
         if let Some(group) = data.groups.iter_mut().find(|g| &g.id == id) {
             group.order = index as i32;
@@ -324,12 +1806,14 @@ pub fn reorder_groups(group_ids: Vec<String>, state: State<AppState>) -> Result<
     }
     
     state.storage.save(&data)?;
+    emit_data_changed(&app, Vec::new(), group_ids);
     Ok(())
 }
 
 #[tauri::command]
 pub fn update_settings(
     mut settings: AppSettings,
+    app: AppHandle,
     state: State<AppState>,
 ) -> Result<(), String> {
     let mut data = state.data.lock()
@@ -376,31 +1860,211 @@ pub fn update_settings(
         println!("🗑️ 关闭记住窗口大小，重置 window_position 为默认值");
     }
     
+    let old_language = data.settings.language;
+    let language_changed = settings.language != old_language;
     data.settings = settings;
+    if language_changed {
+        data.localize_default_group(old_language);
+    }
     state.storage.save(&data)?;
+    if language_changed {
+        emit_data_changed(&app, Vec::new(), vec![data.default_group_id()]);
+    }
     
     Ok(())
 }
 
+/// 未传 options 时导出全部数据（含设置）
 #[tauri::command]
-pub fn export_data(path: String, state: State<AppState>) -> Result<(), String> {
+pub fn export_data(
+    path: String,
+    options: Option<ExportOptions>,
+    state: State<AppState>,
+) -> Result<(), String> {
     let data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    state.storage.export_to_file(&data, &path)?;
+    match options {
+        Some(options) => state.storage.export_to_file(&data.export_selection(&options), &path)?,
+        None => state.storage.export_to_file(&*data, &path)?,
+    }
     
     Ok(())
 }
 
+/// 读取导出文件的概要，用于导入前决定合并还是替换
+#[tauri::command]
+pub fn read_export_info(path: String, state: State<AppState>) -> Result<ExportInfo, String> {
+    let imported = state.storage.import_from_file(&path)?;
+    Ok(ExportInfo {
+        partial: imported.partial,
+        todos: imported.todos.len(),
+        groups: imported.groups.len(),
+    })
+}
+
+/// merge 为 true 时按 id 合并到现有数据，否则整体替换
 #[tauri::command]
-pub fn import_data(path: String, state: State<AppState>) -> Result<(), String> {
+pub fn import_data(
+    path: String,
+    merge: Option<bool>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), String> {
     let imported_data = state.storage.import_from_file(&path)?;
     
     let mut data = state.data.lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    *data = imported_data;
+    // 部分导出只包含选中的数据，替换会丢掉本机其余数据
+    if merge.unwrap_or(false) {
+        data.merge(imported_data);
+    } else if imported_data.partial {
+        return Err("This file is a partial export and can only be merged".to_string());
+    } else {
+        // 导出文件不含设置时保留本机设置
+        let settings = imported_data.settings.unwrap_or_else(|| data.settings.clone());
+        *data = AppData {
+            todos: imported_data.todos,
+            groups: imported_data.groups,
+            settings,
+            time_entries: imported_data.time_entries,
+            focus_sessions: imported_data.focus_sessions,
+            templates: imported_data.templates,
+            version: imported_data.version,
+        };
+        data.ensure_default_group();
+    }
+    state.storage.save(&data)?;
+    emit_data_reload(&app);
+    
+    Ok(())
+}
+
+/// 导出 todo.txt，返回无法原样导回的标题说明
+#[tauri::command]
+pub fn export_todotxt(path: String, state: State<AppState>) -> Result<Vec<String>, String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let content = todotxt::export(&data);
+    state.storage.export_text_to_file(&content, &path)?;
+    
+    Ok(todotxt::misread_titles(&data))
+}
+
+#[tauri::command]
+pub fn import_todotxt(path: String, app: AppHandle, state: State<AppState>) -> Result<ImportSummary, String> {
+    let content = state.storage.import_text_from_file(&path)?;
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let summary = todotxt::import(&content, &mut data);
+    state.storage.save(&data)?;
+    emit_data_reload(&app);
+    
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn export_csv(path: String, state: State<AppState>) -> Result<(), String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let content = csv::export(&data);
+    state.storage.export_text_to_file(&content, &path)?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn read_csv_headers(path: String, state: State<AppState>) -> Result<Vec<String>, String> {
+    let content = state.storage.import_text_from_file(&path)?;
+    Ok(csv::headers(&content))
+}
+
+#[tauri::command]
+pub fn import_csv(
+    path: String,
+    mapping: CsvColumnMapping,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<ImportSummary, String> {
+    let content = state.storage.import_text_from_file(&path)?;
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let summary = csv::import(&content, &mapping, &mut data)?;
+    state.storage.save(&data)?;
+    emit_data_reload(&app);
+    
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn export_org(path: String, state: State<AppState>) -> Result<(), String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let content = org::export(&data);
+    state.storage.export_text_to_file(&content, &path)?;
+    
+    Ok(())
+}
+
+/// 导入 Todoist 备份：.json 为完整备份，其余按单个项目的 CSV 处理
+#[tauri::command]
+pub fn import_todoist(path: String, app: AppHandle, state: State<AppState>) -> Result<ImportSummary, String> {
+    let content = state.storage.import_text_from_file(&path)?;
+    let file_path = std::path::Path::new(&path);
+    let is_json = file_path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let summary = if is_json {
+        todoist::import_json(&content, &mut data)?
+    } else {
+        let project_name = file_path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Todoist".to_string());
+        todoist::import_csv(&content, &project_name, &mut data)?
+    };
+    state.storage.save(&data)?;
+    emit_data_reload(&app);
+    
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn import_microsoft_todo(path: String, app: AppHandle, state: State<AppState>) -> Result<ImportSummary, String> {
+    let content = state.storage.import_text_from_file(&path)?;
+    
+    let mut data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let summary = mstodo::import_json(&content, &mut data)?;
     state.storage.save(&data)?;
+    emit_data_reload(&app);
+    
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn export_report(
+    path: String,
+    options: Option<ReportOptions>,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let data = state.data.lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let content = report::render(&data, &options.unwrap_or_default());
+    state.storage.export_text_to_file(&content, &path)?;
     
     Ok(())
 }
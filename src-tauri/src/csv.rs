@@ -0,0 +1,430 @@
+use crate::models::*;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+// Excel 需要 BOM 才能正确识别 UTF-8 中文
+const UTF8_BOM: char = '\u{feff}';
+
+const EXPORT_HEADERS: [&str; 15] = [
+    "ID",
+    "Title",
+    "Details",
+    "Group",
+    "Color",
+    "Tags",
+    "Priority",
+    "Start",
+    "Due",
+    "Completed",
+    "Completed at",
+    "Archived",
+    "Created",
+    "Updated",
+    "Next time node",
+];
+
+/// 导入时 CSV 列名到待办字段的映射，未指定的字段使用默认值。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CsvColumnMapping {
+    pub title: String,
+    pub details: Option<String>,
+    pub group: Option<String>,
+    pub color: Option<String>,
+    pub tags: Option<String>,
+    pub priority: Option<String>,
+    pub start_date: Option<String>,
+    pub due_date: Option<String>,
+    pub completed: Option<String>,
+    pub completed_at: Option<String>,
+    pub archived: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub time_node: Option<String>,
+}
+
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn format_date_time(date_time: &DateTime<Utc>) -> String {
+    date_time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+fn next_time_node(todo: &TodoItem, now: DateTime<Utc>) -> Option<&TimeNode> {
+    todo.time_nodes.iter()
+        .filter(|tn| tn.date_time >= now)
+        .min_by_key(|tn| tn.date_time)
+}
+
+// 自定义字段按名称合并成列，不同分组中的同名字段共用一列
+fn custom_field_columns(data: &AppData) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for field in data.group_tree().into_iter().flat_map(|(_, g)| g.custom_fields.iter()) {
+        if !columns.contains(&field.name) {
+            columns.push(field.name.clone());
+        }
+    }
+    columns
+}
+
+fn custom_field_cell(group: Option<&TodoGroup>, todo: &TodoItem, column: &str) -> String {
+    let Some(field) = group.and_then(|g| g.custom_fields.iter().find(|f| f.name == column)) else {
+        return String::new();
+    };
+    match (&field.field_type, todo.custom_fields.get(&field.id)) {
+        (CustomFieldType::Date, Some(CustomFieldValue::Text(value))) => parse_date_time(value)
+            .map(|d| format_date_time(&d))
+            .unwrap_or_else(|| value.clone()),
+        (_, Some(value)) => value.display(),
+        (_, None) => String::new(),
+    }
+}
+
+/// 导出为 CSV（带 BOM、CRLF 换行），每个待办一行；自定义字段排在固定列之后。
+pub fn export(data: &AppData) -> String {
+    let now = Utc::now();
+    let todos = data.sort_in_tree(data.todos.clone());
+    let custom_columns = custom_field_columns(data);
+
+    let mut content = String::new();
+    content.push(UTF8_BOM);
+    let headers: Vec<String> = EXPORT_HEADERS.iter()
+        .map(|h| h.to_string())
+        .chain(custom_columns.iter().map(|c| escape_field(c)))
+        .collect();
+    content.push_str(&headers.join(","));
+    content.push_str("\r\n");
+
+    for todo in &todos {
+        let group = data.groups.iter().find(|g| g.id == todo.group_id);
+        let group_name = group.map(|g| g.name.as_str()).unwrap_or("");
+        let row = [
+            todo.id.clone(),
+            todo.title.clone(),
+            todo.details.clone().unwrap_or_default(),
+            group_name.to_string(),
+            format!("{:?}", todo.color_tag),
+            todo.tags.join(", "),
+            format!("{:?}", todo.priority),
+            todo.start_date.as_ref().map(format_date_time).unwrap_or_default(),
+            todo.due_date.as_ref().map(format_date_time).unwrap_or_default(),
+            todo.completed.to_string(),
+            todo.completed_at.as_ref().map(format_date_time).unwrap_or_default(),
+            todo.archived.to_string(),
+            format_date_time(&todo.created_at),
+            format_date_time(&todo.updated_at),
+            next_time_node(todo, now).map(|tn| format_date_time(&tn.date_time)).unwrap_or_default(),
+        ];
+        let fields: Vec<String> = row.iter()
+            .map(|f| escape_field(f))
+            .chain(custom_columns.iter().map(|c| escape_field(&custom_field_cell(group, todo, c))))
+            .collect();
+        content.push_str(&fields.join(","));
+        content.push_str("\r\n");
+    }
+
+    content
+}
+
+// 根据首行猜测分隔符（部分地区的 Excel 使用分号）
+fn detect_delimiter(content: &str) -> char {
+    let first_line = content.lines().next().unwrap_or("");
+    if first_line.contains(',') {
+        ','
+    } else if first_line.contains(';') {
+        ';'
+    } else if first_line.contains('\t') {
+        '\t'
+    } else {
+        ','
+    }
+}
+
+/// 按 RFC 4180 解析 CSV，支持引号内的逗号、换行和转义引号。
+pub fn parse_records(content: &str) -> Vec<Vec<String>> {
+    let content = content.strip_prefix(UTF8_BOM).unwrap_or(content);
+    let delimiter = detect_delimiter(content);
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == '"' && field.is_empty() {
+            in_quotes = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\r' || c == '\n' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            records.push(std::mem::take(&mut record));
+        } else {
+            field.push(c);
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    // 丢弃空行
+    records.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    records
+}
+
+/// 读取表头，供前端构建列映射。
+pub fn headers(content: &str) -> Vec<String> {
+    parse_records(content).into_iter().next().unwrap_or_default()
+}
+
+pub fn parse_bool(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "true" | "1" | "yes" | "y" | "x" | "✓" | "是" | "完成" | "已完成"
+    )
+}
+
+pub fn parse_color_tag(value: &str) -> Option<ColorTag> {
+    let tag = match value.trim().to_lowercase().as_str() {
+        "red1" | "red" | "红" | "红色" => ColorTag::Red1,
+        "orange2" | "orange" | "橙" | "橙色" => ColorTag::Orange2,
+        "yellow3" | "yellow" | "黄" | "黄色" => ColorTag::Yellow3,
+        "green4" | "green" | "绿" | "绿色" => ColorTag::Green4,
+        "cyan5" | "cyan" | "青" | "青色" => ColorTag::Cyan5,
+        "blue6" | "blue" | "蓝" | "蓝色" => ColorTag::Blue6,
+        "purple7" | "purple" | "紫" | "紫色" => ColorTag::Purple7,
+        _ => return None,
+    };
+    Some(tag)
+}
+
+pub fn parse_priority(value: &str) -> Option<Priority> {
+    let priority = match value.trim().to_lowercase().as_str() {
+        "urgent" | "紧急" => Priority::Urgent,
+        "high" | "高" => Priority::High,
+        "medium" | "中" => Priority::Medium,
+        "low" | "低" => Priority::Low,
+        "none" | "无" => Priority::None,
+        _ => return None,
+    };
+    Some(priority)
+}
+
+/// 解析常见的日期时间写法（RFC 3339 或 Excel 本地时间格式）。
+pub fn parse_date_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Some(date_time.with_timezone(&Utc));
+    }
+
+    const DATE_TIME_FORMATS: [&str; 5] = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y/%m/%d %H:%M",
+    ];
+    const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%Y/%m/%d"];
+
+    let naive = DATE_TIME_FORMATS.iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+        .or_else(|| {
+            DATE_FORMATS.iter()
+                .find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })?;
+
+    Local.from_local_datetime(&naive)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// 按列映射导入 CSV，追加到现有数据；缺少标题的行会被跳过。
+pub fn import(content: &str, mapping: &CsvColumnMapping, data: &mut AppData) -> Result<ImportSummary, String> {
+    let mut records = parse_records(content).into_iter();
+    let header = records.next().ok_or_else(|| "CSV file is empty".to_string())?;
+
+    let column = |name: &Option<String>| -> Option<usize> {
+        name.as_ref().and_then(|n| header.iter().position(|h| h.trim() == n.trim()))
+    };
+    let title_column = column(&Some(mapping.title.clone()))
+        .ok_or_else(|| format!("Column not found: {}", mapping.title))?;
+    let details_column = column(&mapping.details);
+    let group_column = column(&mapping.group);
+    let color_column = column(&mapping.color);
+    let tags_column = column(&mapping.tags);
+    let priority_column = column(&mapping.priority);
+    let start_column = column(&mapping.start_date);
+    let due_column = column(&mapping.due_date);
+    let completed_column = column(&mapping.completed);
+    let completed_at_column = column(&mapping.completed_at);
+    let archived_column = column(&mapping.archived);
+    let created_column = column(&mapping.created_at);
+    let updated_column = column(&mapping.updated_at);
+    let time_node_column = column(&mapping.time_node);
+
+    let mut summary = ImportSummary::default();
+    let default_group_id = data.default_group_id();
+
+    for record in records {
+        let cell = |index: Option<usize>| -> &str {
+            index.and_then(|i| record.get(i)).map(|v| v.trim()).unwrap_or("")
+        };
+
+        let title = cell(Some(title_column));
+        if title.is_empty() {
+            continue;
+        }
+
+        let group_id = match cell(group_column) {
+            "" => default_group_id.clone(),
+            name => {
+                let (id, created) = data.find_or_create_group(name);
+                if created {
+                    summary.groups_created += 1;
+                }
+                id
+            }
+        };
+
+        let order = data.next_todo_order(&group_id, None);
+        let mut todo = TodoItem::new(title.to_string(), group_id, order);
+        let details = cell(details_column);
+        if !details.is_empty() {
+            todo.details = Some(details.to_string());
+        }
+        todo.color_tag = parse_color_tag(cell(color_column)).unwrap_or_default();
+        todo.tags = normalize_tags(cell(tags_column).split([',', ';']).map(|t| t.to_string()).collect());
+        todo.priority = parse_priority(cell(priority_column)).unwrap_or_default();
+        todo.start_date = parse_date_time(cell(start_column));
+        todo.due_date = parse_date_time(cell(due_column));
+        todo.completed = parse_bool(cell(completed_column));
+        if parse_bool(cell(archived_column)) {
+            todo.archived = true;
+            todo.archived_at = Some(Utc::now());
+        }
+        if let Some(created_at) = parse_date_time(cell(created_column)) {
+            todo.created_at = created_at;
+        }
+        todo.updated_at = parse_date_time(cell(updated_column)).unwrap_or(todo.created_at);
+        if todo.completed {
+            todo.completed_at = parse_date_time(cell(completed_at_column)).or(Some(todo.updated_at));
+        }
+
+        if let Some(date_time) = parse_date_time(cell(time_node_column)) {
+            todo.time_nodes.push(TimeNode::new(date_time, None));
+        }
+
+        data.todos.push(todo);
+        summary.todos_imported += 1;
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> CsvColumnMapping {
+        CsvColumnMapping {
+            title: "Title".to_string(),
+            details: Some("Details".to_string()),
+            group: Some("Group".to_string()),
+            color: Some("Color".to_string()),
+            tags: Some("Tags".to_string()),
+            priority: Some("Priority".to_string()),
+            start_date: Some("Start".to_string()),
+            due_date: Some("Due".to_string()),
+            completed: Some("Completed".to_string()),
+            completed_at: Some("Completed at".to_string()),
+            archived: Some("Archived".to_string()),
+            created_at: Some("Created".to_string()),
+            updated_at: Some("Updated".to_string()),
+            time_node: Some("Next time node".to_string()),
+        }
+    }
+
+    fn local(value: &str) -> DateTime<Utc> {
+        parse_date_time(value).unwrap()
+    }
+
+    // 导入时会重新生成 ID，比较时去掉 ID 列
+    fn rows_without_ids(content: &str) -> Vec<Vec<String>> {
+        parse_records(content).into_iter().map(|row| row[1..].to_vec()).collect()
+    }
+
+    #[test]
+    fn round_trip_keeps_quoting() {
+        let mut data = AppData::default();
+        let (work_id, _) = data.find_or_create_group("Work, \"Team\"");
+
+        let mut first = TodoItem::new("Buy \"milk\", eggs".to_string(), data.default_group_id(), 0.0);
+        first.details = Some("line one\nline two, with \"quotes\"".to_string());
+        first.tags = vec!["errand".to_string(), "home".to_string()];
+        first.color_tag = ColorTag::Red1;
+        first.priority = Priority::High;
+        first.start_date = Some(local("2099-04-01 08:00"));
+        first.due_date = Some(local("2099-05-01 09:30"));
+        first.time_nodes.push(TimeNode::new(local("2099-04-30 08:00"), None));
+        first.created_at = local("2024-01-02 10:00");
+        first.updated_at = local("2024-01-03 11:15");
+
+        let mut second = TodoItem::new("Ship".to_string(), work_id, 0.0);
+        second.set_completed(true, local("2024-02-01 17:45"));
+        second.created_at = local("2024-01-20 09:00");
+        second.updated_at = local("2024-02-01 17:45");
+        data.todos.extend([first, second]);
+
+        let content = export(&data);
+        assert!(content.starts_with(UTF8_BOM));
+        assert!(content.contains("\"Buy \"\"milk\"\", eggs\""));
+        assert!(content.contains("\"line one\nline two, with \"\"quotes\"\"\""));
+        assert!(content.ends_with("\r\n"));
+
+        let mut imported = AppData::default();
+        let summary = import(&content, &mapping(), &mut imported).unwrap();
+        assert_eq!(summary.todos_imported, 2);
+        assert_eq!(summary.groups_created, 1);
+        assert_eq!(imported.todos[0].title, data.todos[0].title);
+        assert_eq!(imported.todos[0].details, data.todos[0].details);
+        assert_eq!(imported.todos[1].completed_at, data.todos[1].completed_at);
+
+        assert_eq!(rows_without_ids(&export(&imported)), rows_without_ids(&content));
+    }
+
+    #[test]
+    fn import_strips_bom_and_detects_delimiter() {
+        let mapping = CsvColumnMapping {
+            title: "Title".to_string(),
+            details: Some("Details".to_string()),
+            ..Default::default()
+        };
+        let mut data = AppData::default();
+        import("\u{feff}Title;Details\r\n\"a; b\";\"say \"\"hi\"\"\"\r\n", &mapping, &mut data).unwrap();
+        assert_eq!(data.todos[0].title, "a; b");
+        assert_eq!(data.todos[0].details.as_deref(), Some("say \"hi\""));
+    }
+}
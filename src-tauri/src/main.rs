@@ -4,6 +4,16 @@
 mod models;
 mod storage;
 mod commands;
+mod todotxt;
+mod csv;
+mod org;
+mod todoist;
+mod mstodo;
+mod report;
+mod pomodoro;
+mod notifier;
+mod stats;
+mod templates;
 
 use std::sync::Mutex;
 use tauri::{CustomMenuItem, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem, Manager};
@@ -59,31 +69,93 @@ fn main() {
             let data = storage.load()
                 .unwrap_or_else(|_| models::AppData::default());
             
+            // 重启后继续显示未结束的计时
+            update_tray_tooltip(&app.handle(), &data);
+            
             let app_state = AppState {
                 data: Mutex::new(data),
                 storage,
+                pomodoro: Mutex::new(pomodoro::Pomodoro::default()),
             };
             
             app.manage(app_state);
             
+            // 时间节点提醒与番茄钟在后台线程中运行
+            notifier::spawn(app.handle());
+            
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_all_data,
+            list_todos,
+            get_todo,
+            get_todos,
+            list_groups,
+            list_archived,
+            unarchive_todos,
+            purge_archived,
             create_todo,
             update_todo,
             delete_todo,
+            add_dependency,
+            remove_dependency,
+            get_ready_todos,
+            get_blocked_todos,
+            get_todo_history,
+            add_file_attachment,
+            add_url_attachment,
+            add_todo_reference,
+            remove_attachment,
+            open_attachment,
+            duplicate_todo,
+            list_templates,
+            create_template_from_todo,
+            update_template,
+            delete_template,
+            instantiate_template,
+            start_timer,
+            stop_timer,
+            get_running_timer,
+            list_time_entries,
+            get_time_totals,
+            start_pomodoro,
+            stop_pomodoro,
+            get_pomodoro_status,
+            get_focus_stats,
+            get_effort_rollup,
+            get_group_effort_summary,
+            get_stats,
             reorder_todos,
+            move_todos,
+            reposition_todo,
+            search_todos,
+            sort_todos,
+            list_tags,
+            rename_tag,
+            merge_tags,
+            delete_tag,
             add_time_node,
             update_time_node,
             delete_time_node,
             create_group,
             update_group,
+            set_default_group,
+            set_group_custom_fields,
             delete_group,
             reorder_groups,
             update_settings,
             export_data,
+            read_export_info,
             import_data,
+            export_todotxt,
+            import_todotxt,
+            export_csv,
+            read_csv_headers,
+            import_csv,
+            export_org,
+            import_todoist,
+            import_microsoft_todo,
+            export_report,
             get_window_position,
             get_monitor_info,
             update_window_position,
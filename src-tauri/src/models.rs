@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TodoItem {
@@ -9,16 +11,214 @@ pub struct TodoItem {
     pub group_id: String,
     pub color_tag: ColorTag,
     pub completed: bool,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
     pub hidden: bool,
     pub archived: bool,
     pub archived_at: Option<DateTime<Utc>>,
-    pub order: i32,
+    // 同一分组、同一父待办下的顺序，可以是小数
+    pub order: f64,
     pub parent_id: Option<String>,
     pub time_nodes: Vec<TimeNode>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub due_date: Option<DateTime<Utc>>,
+    // 已完成的番茄数
+    #[serde(default)]
+    pub pomodoros: u32,
+    // 预估工作量（分钟），不含子待办
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    // 依赖的待办 id，这些待办全部完成前本待办处于阻塞状态
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    // 变更记录，只追加；超过上限时丢弃最早的记录
+    #[serde(default)]
+    pub history: Vec<HistoryEntry>,
+    // 自定义字段取值，键为所属分组中的字段 id
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum AttachmentKind {
+    // copied 为 true 时文件位于应用数据目录，删除附件或待办时一并删除
+    File { path: String, name: String, copied: bool },
+    Url { url: String, title: Option<String> },
+    TodoRef { todo_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: AttachmentKind,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    pub fn new(kind: AttachmentKind) -> Self {
+        Attachment {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            created_at: Utc::now(),
+        }
+    }
+
+    // 用于变更记录和显示的简短描述
+    pub fn label(&self) -> String {
+        match &self.kind {
+            AttachmentKind::File { name, .. } => name.clone(),
+            AttachmentKind::Url { url, title } => title.clone().unwrap_or_else(|| url.clone()),
+            AttachmentKind::TodoRef { todo_id } => format!("#{}", todo_id),
+        }
+    }
+
+    // 复制进应用数据目录的文件路径
+    pub fn copied_path(&self) -> Option<&str> {
+        match &self.kind {
+            AttachmentKind::File { path, copied: true, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+pub const HISTORY_LIMIT: usize = 200;
+// 同一字段在该时间内的连续修改合并为一条
+const HISTORY_COMPACT_SECONDS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+pub fn date_value(date_time: &Option<DateTime<Utc>>) -> Option<String> {
+    date_time.map(|d| d.to_rfc3339())
+}
+
+pub fn time_node_value(node: &TimeNode) -> String {
+    format!("{} {}", node.date_time.to_rfc3339(), node.description.as_deref().unwrap_or("")).trim_end().to_string()
+}
+
+impl TodoItem {
+    // 记录一次字段变更，值未变化时忽略
+    pub fn record_change(&mut self, field: &str, old_value: Option<String>, new_value: Option<String>) {
+        if old_value == new_value {
+            return;
+        }
+        let now = Utc::now();
+
+        if let Some(last) = self.history.last_mut() {
+            if last.field == field
+                && last.new_value == old_value
+                && (now - last.at).num_seconds() < HISTORY_COMPACT_SECONDS
+            {
+                last.new_value = new_value;
+                last.at = now;
+                // 改回了原值，这条记录不再有意义
+                if last.old_value == last.new_value {
+                    self.history.pop();
+                }
+                return;
+            }
+        }
+
+        self.history.push(HistoryEntry {
+            field: field.to_string(),
+            old_value,
+            new_value,
+            at: now,
+        });
+        if self.history.len() > HISTORY_LIMIT {
+            let excess = self.history.len() - HISTORY_LIMIT;
+            self.history.drain(..excess);
+        }
+    }
+
+    // 切换完成状态并维护完成时间
+    pub fn set_completed(&mut self, completed: bool, now: DateTime<Utc>) {
+        if completed && !self.completed {
+            self.completed_at = Some(now);
+        } else if !completed {
+            self.completed_at = None;
+        }
+        self.completed = completed;
+    }
+
+    pub fn new(title: String, group_id: String, order: f64) -> Self {
+        let now = Utc::now();
+        TodoItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            title,
+            details: None,
+            group_id,
+            color_tag: ColorTag::default(),
+            completed: false,
+            completed_at: None,
+            hidden: false,
+            archived: false,
+            archived_at: None,
+            order,
+            parent_id: None,
+            time_nodes: Vec::new(),
+            tags: Vec::new(),
+            priority: Priority::default(),
+            start_date: None,
+            due_date: None,
+            pomodoros: 0,
+            estimate_minutes: None,
+            blocked_by: Vec::new(),
+            attachments: Vec::new(),
+            history: Vec::new(),
+            custom_fields: BTreeMap::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    // 替换自定义字段取值，逐个字段记录变更
+    pub fn set_custom_fields(&mut self, values: BTreeMap<String, CustomFieldValue>) {
+        let ids: BTreeSet<String> = self.custom_fields.keys().chain(values.keys()).cloned().collect();
+        for id in ids {
+            let old_value = self.custom_fields.get(&id).map(|v| v.display());
+            let new_value = values.get(&id).map(|v| v.display());
+            self.record_change(&format!("custom_fields.{}", id), old_value, new_value);
+        }
+        self.custom_fields = values;
+    }
+}
+
+// 去掉首尾空白和前缀 #，丢弃空标签并去重（保留原顺序）
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').trim().to_string();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagCount {
+    pub name: String,
+    pub count: usize,
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubTask {
@@ -33,11 +233,182 @@ pub struct SubTask {
 pub struct TodoGroup {
     pub id: String,
     pub name: String,
+    // 上级分组，None 为顶层；order 为同一上级下的顺序
+    #[serde(default)]
+    pub parent_group_id: Option<String>,
     pub order: i32,
     pub is_default: bool,
+    // 十六进制颜色，如 "#4a90e2"
+    #[serde(default)]
+    pub color: Option<String>,
+    // emoji 或图标名
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub defaults: GroupDefaults,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomFieldDef>,
     pub created_at: DateTime<Utc>,
 }
 
+// 组内新建待办和时间节点未指定时使用的默认值
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct GroupDefaults {
+    pub color_tag: Option<ColorTag>,
+    // 设置后新时间节点默认开启提醒，提前这么多分钟
+    pub reminder_minutes_before: Option<i32>,
+}
+
+// 只接受 #RGB 或 #RRGGBB
+pub fn is_hex_color(value: &str) -> bool {
+    let Some(digits) = value.strip_prefix('#') else {
+        return false;
+    };
+    matches!(digits.len(), 3 | 6) && digits.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl TodoGroup {
+    pub fn new(name: String, parent_group_id: Option<String>, order: i32) -> Self {
+        TodoGroup {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            parent_group_id,
+            order,
+            is_default: false,
+            color: None,
+            icon: None,
+            description: None,
+            defaults: GroupDefaults::default(),
+            custom_fields: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    // 按本分组的字段定义校验并规范化取值，未定义的字段报错
+    pub fn validate_custom_fields(
+        &self,
+        values: BTreeMap<String, CustomFieldValue>,
+    ) -> Result<BTreeMap<String, CustomFieldValue>, String> {
+        let mut normalized = BTreeMap::new();
+        for (id, value) in values {
+            let field = self.custom_fields.iter()
+                .find(|f| f.id == id)
+                .ok_or_else(|| format!("Unknown custom field: {}", id))?;
+            if let Some(value) = field.normalize_value(value)? {
+                normalized.insert(id, value);
+            }
+        }
+        Ok(normalized)
+    }
+
+    // 只保留仍符合字段定义的取值，用于字段定义变化或待办换组之后
+    pub fn retain_custom_fields(&self, values: &BTreeMap<String, CustomFieldValue>) -> BTreeMap<String, CustomFieldValue> {
+        values.iter()
+            .filter_map(|(id, value)| {
+                let field = self.custom_fields.iter().find(|f| &f.id == id)?;
+                let value = field.normalize_value(value.clone()).ok()??;
+                Some((id.clone(), value))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    // 取值为 RFC 3339 时间字符串
+    Date,
+    Select { options: Vec<String> },
+    Checkbox,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomFieldDef {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub field_type: CustomFieldType,
+}
+
+impl CustomFieldDef {
+    // 检查取值与字段类型是否相符；空文本视为未填写
+    pub fn normalize_value(&self, value: CustomFieldValue) -> Result<Option<CustomFieldValue>, String> {
+        let invalid = || format!("Invalid value for custom field {}", self.name);
+        match (&self.field_type, value) {
+            (_, CustomFieldValue::Text(text)) if text.trim().is_empty() => Ok(None),
+            (CustomFieldType::Text, CustomFieldValue::Text(text)) => Ok(Some(CustomFieldValue::Text(text))),
+            (CustomFieldType::Number, CustomFieldValue::Number(number)) => Ok(Some(CustomFieldValue::Number(number))),
+            (CustomFieldType::Date, CustomFieldValue::Text(text)) => DateTime::parse_from_rfc3339(text.trim())
+                .map(|d| Some(CustomFieldValue::Text(d.with_timezone(&Utc).to_rfc3339())))
+                .map_err(|_| invalid()),
+            (CustomFieldType::Select { options }, CustomFieldValue::Text(text)) => options.iter()
+                .find(|o| o.as_str() == text.trim())
+                .map(|o| Some(CustomFieldValue::Text(o.clone())))
+                .ok_or_else(invalid),
+            (CustomFieldType::Checkbox, CustomFieldValue::Checkbox(checked)) => Ok(Some(CustomFieldValue::Checkbox(checked))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// 规范化分组的字段定义：名称去空白且不重复，缺少 id 的新字段生成 id，选项去重
+pub fn normalize_custom_field_defs(fields: Vec<CustomFieldDef>) -> Result<Vec<CustomFieldDef>, String> {
+    let mut normalized: Vec<CustomFieldDef> = Vec::new();
+    for mut field in fields {
+        field.name = field.name.trim().to_string();
+        if field.name.is_empty() {
+            return Err("Custom field name cannot be empty".to_string());
+        }
+        if normalized.iter().any(|f| f.name.to_lowercase() == field.name.to_lowercase()) {
+            return Err(format!("Duplicate custom field: {}", field.name));
+        }
+        if field.id.trim().is_empty() {
+            field.id = uuid::Uuid::new_v4().to_string();
+        } else if normalized.iter().any(|f| f.id == field.id) {
+            return Err(format!("Duplicate custom field id: {}", field.id));
+        }
+        if let CustomFieldType::Select { options } = &mut field.field_type {
+            let mut unique: Vec<String> = Vec::new();
+            for option in options.iter().map(|o| o.trim()).filter(|o| !o.is_empty()) {
+                if !unique.iter().any(|u| u == option) {
+                    unique.push(option.to_string());
+                }
+            }
+            if unique.is_empty() {
+                return Err(format!("Custom field {} needs at least one option", field.name));
+            }
+            *options = unique;
+        }
+        normalized.push(field);
+    }
+    Ok(normalized)
+}
+
+// 复选框、数字以外的取值（文本、日期、选项）都以字符串保存
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum CustomFieldValue {
+    Checkbox(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl CustomFieldValue {
+    // 搜索、导出和变更记录使用的文本形式
+    pub fn display(&self) -> String {
+        match self {
+            CustomFieldValue::Checkbox(checked) => checked.to_string(),
+            CustomFieldValue::Number(number) => number.to_string(),
+            CustomFieldValue::Text(text) => text.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeNode {
     pub id: String,
@@ -48,6 +419,19 @@ pub struct TimeNode {
     pub created_at: DateTime<Utc>,
 }
 
+impl TimeNode {
+    pub fn new(date_time: DateTime<Utc>, description: Option<String>) -> Self {
+        TimeNode {
+            id: uuid::Uuid::new_v4().to_string(),
+            date_time,
+            description,
+            reminder_enabled: false,
+            reminder_minutes_before: 0,
+            created_at: Utc::now(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum ColorTag {
     Red1,    // 最紧急
@@ -65,6 +449,67 @@ impl Default for ColorTag {
     }
 }
 
+// 显式优先级，与 ColorTag 的颜色含义分开
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl From<ColorTag> for Priority {
+    fn from(color_tag: ColorTag) -> Self {
+        match color_tag {
+            ColorTag::Red1 => Priority::Urgent,
+            ColorTag::Orange2 => Priority::High,
+            ColorTag::Yellow3 => Priority::Medium,
+            ColorTag::Green4 | ColorTag::Cyan5 | ColorTag::Purple7 => Priority::Low,
+            ColorTag::Blue6 => Priority::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SortKey {
+    DueDate,
+    StartDate,
+    Priority,
+    Order,
+    CreatedAt,
+    Title,
+}
+
+// 日期为空的排在最后
+fn compare_optional_dates(a: &Option<DateTime<Utc>>, b: &Option<DateTime<Utc>>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+// 按排序键依次比较；优先级高的在前，其余升序
+pub fn compare_todos(a: &TodoItem, b: &TodoItem, keys: &[SortKey]) -> Ordering {
+    for key in keys {
+        let ordering = match key {
+            SortKey::DueDate => compare_optional_dates(&a.due_date, &b.due_date),
+            SortKey::StartDate => compare_optional_dates(&a.start_date, &b.start_date),
+            SortKey::Priority => b.priority.cmp(&a.priority),
+            SortKey::Order => a.order.total_cmp(&b.order),
+            SortKey::CreatedAt => a.created_at.cmp(&b.created_at),
+            SortKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub theme: Theme,
@@ -76,12 +521,35 @@ pub struct AppSettings {
     pub window_position: WindowPosition,
     #[serde(default = "default_docked_edge")]
     pub default_docked_edge: DockedEdge,
+    #[serde(default)]
+    pub pomodoro: PomodoroSettings,
 }
 
 fn default_docked_edge() -> DockedEdge {
     DockedEdge::Right
 }
 
+// 番茄钟时长（分钟）；每完成 long_break_interval 个番茄进入一次长休息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PomodoroSettings {
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    pub long_break_interval: u32,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        PomodoroSettings {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_interval: 4,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowPosition {
     pub x: f64,
@@ -124,6 +592,17 @@ pub enum Language {
     English,
 }
 
+impl Language {
+    // 初始默认分组的名称；未改名时随语言切换
+    pub fn default_group_name(&self) -> &'static str {
+        match self {
+            Language::SimplifiedChinese => "个人",
+            Language::TraditionalChinese => "個人",
+            Language::English => "Personal",
+        }
+    }
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         AppSettings {
@@ -141,6 +620,7 @@ impl Default for AppSettings {
                 docked_edge: Some(DockedEdge::Right),
             },
             default_docked_edge: DockedEdge::Right,
+            pomodoro: PomodoroSettings::default(),
         }
     }
 }
@@ -150,22 +630,1292 @@ pub struct AppData {
     pub todos: Vec<TodoItem>,
     pub groups: Vec<TodoGroup>,
     pub settings: AppSettings,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    pub focus_sessions: Vec<FocusSession>,
+    #[serde(default)]
+    pub templates: Vec<TodoTemplate>,
+    // 数据格式版本，旧文件缺省为 0，加载后由 migrate 升级
+    #[serde(default)]
+    pub version: u32,
+}
+
+pub const DATA_VERSION: u32 = 2;
+
+// 按版本依次升级待办数据
+fn migrate_todos(todos: &mut [TodoItem], version: u32) {
+    if version < 1 {
+        // v1: 根据颜色标签推导初始优先级
+        for todo in todos.iter_mut() {
+            todo.priority = Priority::from(todo.color_tag);
+        }
+    }
+    if version < 2 {
+        // v2: 旧数据没有完成时间，用最后更新时间近似
+        for todo in todos.iter_mut().filter(|t| t.completed && t.completed_at.is_none()) {
+            todo.completed_at = Some(todo.updated_at);
+        }
+    }
 }
 
 impl Default for AppData {
     fn default() -> Self {
-        let personal_group = TodoGroup {
-            id: "personal".to_string(),
-            name: "个人".to_string(),
-            order: 0,
-            is_default: true,
-            created_at: Utc::now(),
-        };
-
-        AppData {
+        let mut data = AppData {
             todos: Vec::new(),
-            groups: vec![personal_group],
+            groups: Vec::new(),
             settings: AppSettings::default(),
+            time_entries: Vec::new(),
+            focus_sessions: Vec::new(),
+            templates: Vec::new(),
+            version: DATA_VERSION,
+        };
+        data.ensure_default_group();
+        data
+    }
+}
+
+impl AppData {
+    pub fn migrate(&mut self) {
+        if self.version < DATA_VERSION {
+            migrate_todos(&mut self.todos, self.version);
+            self.version = DATA_VERSION;
+        }
+        self.ensure_default_group();
+    }
+
+    // 默认分组 id
+    pub fn default_group_id(&self) -> String {
+        self.groups.iter()
+            .find(|g| g.is_default)
+            .or_else(|| self.groups.first())
+            .map(|g| g.id.clone())
+            .unwrap_or_default()
+    }
+
+    // 保证有且只有一个默认分组：没有分组时按当前语言新建，没有标记时取第一个分组
+    pub fn ensure_default_group(&mut self) {
+        if self.groups.is_empty() {
+            let name = self.settings.language.default_group_name().to_string();
+            self.groups.push(TodoGroup::new(name, None, 0));
+        }
+        let default_id = self.default_group_id();
+        for group in self.groups.iter_mut() {
+            group.is_default = group.id == default_id;
+        }
+    }
+
+    pub fn set_default_group(&mut self, group_id: &str) -> Result<(), String> {
+        if !self.groups.iter().any(|g| g.id == group_id) {
+            return Err("Group not found".to_string());
+        }
+        for group in self.groups.iter_mut() {
+            group.is_default = group.id == group_id;
+        }
+        Ok(())
+    }
+
+    // 切换语言后，仍使用旧语言初始名称的默认分组改用新语言的名称
+    pub fn localize_default_group(&mut self, old_language: Language) {
+        let name = self.settings.language.default_group_name();
+        if let Some(group) = self.groups.iter_mut().find(|g| g.is_default) {
+            if group.name == old_language.default_group_name() {
+                group.name = name.to_string();
+            }
+        }
+    }
+
+    pub fn tag_counts(&self) -> Vec<TagCount> {
+        let mut counts: Vec<TagCount> = Vec::new();
+        for tag in self.todos.iter().flat_map(|t| t.tags.iter()) {
+            match counts.iter_mut().find(|c| &c.name == tag) {
+                Some(count) => count.count += 1,
+                None => counts.push(TagCount { name: tag.clone(), count: 1 }),
+            }
+        }
+        counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+        counts
+    }
+
+    // 把 sources 中的标签替换为 target（target 为 None 时直接删除），返回受影响的待办 id
+    pub fn replace_tags(&mut self, sources: &[String], target: Option<&str>) -> Vec<String> {
+        let mut affected = Vec::new();
+        for todo in self.todos.iter_mut() {
+            if !todo.tags.iter().any(|t| sources.contains(t)) {
+                continue;
+            }
+            let tags = todo.tags.iter()
+                .filter_map(|t| if sources.contains(t) { target.map(|s| s.to_string()) } else { Some(t.clone()) })
+                .collect();
+            let tags = normalize_tags(tags);
+            todo.record_change("tags", Some(todo.tags.join(", ")), Some(tags.join(", ")));
+            todo.tags = tags;
+            todo.updated_at = Utc::now();
+            affected.push(todo.id.clone());
+        }
+        affected
+    }
+
+    // 同一上级下新分组的顺序
+    pub fn next_group_order(&self, parent_group_id: Option<&str>) -> i32 {
+        self.groups.iter()
+            .filter(|g| g.parent_group_id.as_deref() == parent_group_id)
+            .map(|g| g.order + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // 分组及其所有子孙分组的 id，自身在前；parent_group_id 成环时也能结束
+    pub fn group_subtree(&self, group_id: &str) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        let mut stack = vec![group_id.to_string()];
+        while let Some(id) = stack.pop() {
+            if ids.contains(&id) || !self.groups.iter().any(|g| g.id == id) {
+                continue;
+            }
+            stack.extend(self.groups.iter()
+                .filter(|g| g.parent_group_id.as_deref() == Some(id.as_str()))
+                .map(|g| g.id.clone()));
+            ids.push(id);
+        }
+        ids
+    }
+
+    // 按层级展开的分组及其深度：上级在前，同级按 order；上级不存在的视为顶层
+    pub fn group_tree(&self) -> Vec<(usize, &TodoGroup)> {
+        let mut roots: Vec<&TodoGroup> = self.groups.iter()
+            .filter(|g| g.parent_group_id.as_ref().map(|p| !self.groups.iter().any(|o| &o.id == p)).unwrap_or(true))
+            .collect();
+        roots.sort_by_key(|g| g.order);
+
+        let mut tree: Vec<(usize, &TodoGroup)> = Vec::new();
+        let mut stack: Vec<(usize, &TodoGroup)> = roots.into_iter().rev().map(|g| (0, g)).collect();
+        while let Some((depth, group)) = stack.pop() {
+            if tree.iter().any(|(_, g)| g.id == group.id) {
+                continue;
+            }
+            tree.push((depth, group));
+            let mut children: Vec<&TodoGroup> = self.groups.iter()
+                .filter(|g| g.parent_group_id.as_deref() == Some(group.id.as_str()))
+                .collect();
+            children.sort_by_key(|g| g.order);
+            stack.extend(children.into_iter().rev().map(|g| (depth + 1, g)));
+        }
+        // parent_group_id 成环的分组
+        for group in &self.groups {
+            if !tree.iter().any(|(_, g)| g.id == group.id) {
+                tree.push((0, group));
+            }
+        }
+        tree
+    }
+
+    // 从顶层到该分组的名称路径，如 "工作 › 客户 A"
+    pub fn group_path(&self, group_id: &str) -> String {
+        let mut names: Vec<&str> = Vec::new();
+        let mut visited: Vec<&str> = Vec::new();
+        let mut current = self.groups.iter().find(|g| g.id == group_id);
+        while let Some(group) = current {
+            if visited.contains(&group.id.as_str()) {
+                break;
+            }
+            visited.push(&group.id);
+            names.push(&group.name);
+            current = group.parent_group_id.as_ref()
+                .and_then(|p| self.groups.iter().find(|g| &g.id == p));
+        }
+        names.reverse();
+        names.join(" › ")
+    }
+
+    // include_subgroups 为 true 时把筛选的分组展开为包含子孙分组
+    pub fn expand_group_filter(&self, filter: &TodoFilter) -> TodoFilter {
+        let mut filter = filter.clone();
+        if filter.include_subgroups {
+            if let Some(group_ids) = &filter.group_ids {
+                let mut expanded: Vec<String> = Vec::new();
+                for id in group_ids.iter().flat_map(|id| self.group_subtree(id)) {
+                    if !expanded.contains(&id) {
+                        expanded.push(id);
+                    }
+                }
+                filter.group_ids = Some(expanded);
+            }
+            filter.include_subgroups = false;
+        }
+        filter
+    }
+
+    // 按名称查找分组，不存在则新建；返回分组 id 以及是否新建
+    pub fn find_or_create_group(&mut self, name: &str) -> (String, bool) {
+        if let Some(group) = self.groups.iter().find(|g| g.name == name) {
+            return (group.id.clone(), false);
+        }
+
+        let group = TodoGroup::new(name.to_string(), None, self.next_group_order(None));
+        let id = group.id.clone();
+        self.groups.push(group);
+        (id, true)
+    }
+
+    // 分组字段定义变化后，丢弃组内待办中不再有效的取值
+    pub fn prune_custom_fields(&mut self, group_id: &str) {
+        let Some(group) = self.groups.iter().find(|g| g.id == group_id) else {
+            return;
+        };
+        for todo in self.todos.iter_mut().filter(|t| t.group_id == group_id) {
+            let values = group.retain_custom_fields(&todo.custom_fields);
+            if values != todo.custom_fields {
+                todo.set_custom_fields(values);
+                todo.updated_at = Utc::now();
+            }
+        }
+    }
+}
+
+// 在 prev 和 next 之间均匀取 count 个顺序值；间隔过小、无法严格递增时返回 None
+pub fn orders_between(prev: Option<f64>, next: Option<f64>, count: usize) -> Option<Vec<f64>> {
+    let span = count as f64 + 1.0;
+    let (low, high) = match (prev, next) {
+        (Some(prev), Some(next)) => (prev, next),
+        (Some(prev), None) => (prev, prev + span),
+        (None, Some(next)) => (next - span, next),
+        (None, None) => (-1.0, count as f64),
+    };
+    let step = (high - low) / span;
+    let orders: Vec<f64> = (1..=count).map(|i| low + step * i as f64).collect();
+
+    let mut bounds = vec![low];
+    bounds.extend(&orders);
+    bounds.push(high);
+    bounds.windows(2).all(|w| w[0] < w[1]).then_some(orders)
+}
+
+// 待办顺序：order 只在同一分组、同一父待办的兄弟之间比较，可以是小数，
+// 移动单个待办时只改它自己的 order
+impl AppData {
+    // 兄弟待办末尾的下一个顺序值
+    pub fn next_todo_order(&self, group_id: &str, parent_id: Option<&str>) -> f64 {
+        self.todos.iter()
+            .filter(|t| t.group_id == group_id && t.parent_id.as_deref() == parent_id)
+            .map(|t| t.order.floor() + 1.0)
+            .fold(0.0, f64::max)
+    }
+
+    // 把 ids 放到兄弟待办中的 position 处（不计 ids 自身，None 为末尾）；
+    // ids 需已在该分组和父待办下。相邻顺序值之间没有间隔时整组重新编号
+    fn insert_into_scope(&mut self, group_id: &str, parent_id: Option<&str>, ids: &[String], position: Option<usize>) {
+        let mut siblings: Vec<&TodoItem> = self.todos.iter()
+            .filter(|t| t.group_id == group_id && t.parent_id.as_deref() == parent_id && !ids.contains(&t.id))
+            .collect();
+        siblings.sort_by(|a, b| a.order.total_cmp(&b.order));
+        let at = position.unwrap_or(siblings.len()).min(siblings.len());
+        let prev = at.checked_sub(1).map(|i| siblings[i].order);
+        let next = siblings.get(at).map(|t| t.order);
+
+        let assigned: Vec<(String, f64)> = match orders_between(prev, next, ids.len()) {
+            Some(orders) => ids.iter().cloned().zip(orders).collect(),
+            None => {
+                let mut sequence: Vec<String> = siblings.iter().map(|t| t.id.clone()).collect();
+                sequence.splice(at..at, ids.iter().cloned());
+                sequence.into_iter().enumerate().map(|(index, id)| (id, index as f64)).collect()
+            }
+        };
+        for todo in self.todos.iter_mut() {
+            if let Some((_, order)) = assigned.iter().find(|(id, _)| id == &todo.id) {
+                todo.order = *order;
+            }
+        }
+    }
+
+    // 父待办已被删除的子待办提升为顶层待办，按原有顺序放到所在分组顶层的末尾。
+    // 返回被提升的待办 id
+    pub fn promote_orphans(&mut self, removed_ids: &[String]) -> Vec<String> {
+        let orphans: Vec<TodoItem> = self.todos.iter()
+            .filter(|t| t.parent_id.as_ref().map(|p| removed_ids.contains(p)).unwrap_or(false))
+            .cloned()
+            .collect();
+        let orphans: Vec<(String, String)> = self.sort_in_tree(orphans).into_iter()
+            .map(|t| (t.id, t.group_id))
+            .collect();
+
+        let now = Utc::now();
+        for todo in self.todos.iter_mut() {
+            if orphans.iter().any(|(id, _)| id == &todo.id) {
+                todo.record_change("parent_id", todo.parent_id.clone(), None);
+                todo.parent_id = None;
+                todo.updated_at = now;
+            }
+        }
+        let mut scopes: Vec<(String, Vec<String>)> = Vec::new();
+        for (id, group_id) in &orphans {
+            match scopes.iter_mut().find(|(g, _)| g == group_id) {
+                Some((_, ids)) => ids.push(id.clone()),
+                None => scopes.push((group_id.clone(), vec![id.clone()])),
+            }
+        }
+        for (group_id, ids) in scopes {
+            self.insert_into_scope(&group_id, None, &ids, None);
+        }
+        orphans.into_iter().map(|(id, _)| id).collect()
+    }
+
+    // 按分组树、父子关系和兄弟顺序排列：子待办紧跟在父待办之后。
+    // order 只在兄弟之间有意义，跨分组、跨父待办不能直接比较
+    pub fn sort_in_tree(&self, todos: Vec<TodoItem>) -> Vec<TodoItem> {
+        let group_rank: HashMap<&str, usize> = self.group_tree().into_iter()
+            .enumerate()
+            .map(|(rank, (_, g))| (g.id.as_str(), rank))
+            .collect();
+        let by_id: HashMap<&str, &TodoItem> = self.todos.iter().map(|t| (t.id.as_str(), t)).collect();
+
+        // 从最上层祖先到自身的 (order, id) 路径
+        let path = |todo: &TodoItem| -> Vec<(f64, String)> {
+            let mut path = vec![(todo.order, todo.id.clone())];
+            let mut current = todo.parent_id.as_deref().and_then(|p| by_id.get(p));
+            while let Some(parent) = current {
+                if path.iter().any(|(_, id)| id == &parent.id) {
+                    break;
+                }
+                path.push((parent.order, parent.id.clone()));
+                current = parent.parent_id.as_deref().and_then(|p| by_id.get(p));
+            }
+            path.reverse();
+            path
+        };
+
+        let mut keyed: Vec<_> = todos.into_iter()
+            .map(|t| (group_rank.get(t.group_id.as_str()).copied().unwrap_or(usize::MAX), path(&t), t))
+            .collect();
+        keyed.sort_by(|(rank_a, path_a, _), (rank_b, path_b, _)| {
+            rank_a.cmp(rank_b).then_with(|| {
+                path_a.iter().zip(path_b)
+                    .map(|((order_a, id_a), (order_b, id_b))| order_a.total_cmp(order_b).then_with(|| id_a.cmp(id_b)))
+                    .find(|o| o.is_ne())
+                    .unwrap_or_else(|| path_a.len().cmp(&path_b.len()))
+            })
+        });
+        keyed.into_iter().map(|(_, _, t)| t).collect()
+    }
+
+    // 先按分组树和父子关系排列，再按排序键稳定排序。SortKey::Order 表示树中的位置，
+    // 排在它之后的排序键不再起作用
+    pub fn sort_todos_by(&self, todos: Vec<TodoItem>, keys: &[SortKey]) -> Vec<TodoItem> {
+        let keys: Vec<SortKey> = keys.iter().copied().take_while(|k| *k != SortKey::Order).collect();
+        let mut todos = self.sort_in_tree(todos);
+        todos.sort_by(|a, b| compare_todos(a, b, &keys));
+        todos
+    }
+
+    // 把待办放到兄弟待办 after_id 之后，after_id 为 None 时放到最前
+    pub fn place_todo_after(&mut self, id: &str, after_id: Option<&str>) -> Result<(), String> {
+        let todo = self.todos.iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| "Todo not found".to_string())?;
+        let group_id = todo.group_id.clone();
+        let parent_id = todo.parent_id.clone();
+
+        let position = match after_id {
+            Some(after_id) => {
+                let mut siblings: Vec<&TodoItem> = self.todos.iter()
+                    .filter(|t| t.group_id == group_id && t.parent_id == parent_id && t.id != id)
+                    .collect();
+                siblings.sort_by(|a, b| a.order.total_cmp(&b.order));
+                siblings.iter()
+                    .position(|t| t.id == after_id)
+                    .map(|index| index + 1)
+                    .ok_or_else(|| "Todo is not a sibling".to_string())?
+            }
+            None => 0,
+        };
+        self.insert_into_scope(&group_id, parent_id.as_deref(), &[id.to_string()], Some(position));
+        Ok(())
+    }
+
+    // 把待办连同子孙移到目标分组，子孙保持原有顺序；被移动的最上层待办放到
+    // 目标中兄弟待办的 position 处（None 为末尾）。返回被移动的待办 id，父待办在前
+    pub fn move_todos(&mut self, ids: &[String], target_group_id: &str, position: Option<usize>) -> Result<Vec<String>, String> {
+        let target_group = self.groups.iter()
+            .find(|g| g.id == target_group_id)
+            .cloned()
+            .ok_or_else(|| "Group not found".to_string())?;
+        if let Some(id) = ids.iter().find(|id| !self.todos.iter().any(|t| &t.id == *id)) {
+            return Err(format!("Todo not found: {}", id));
+        }
+
+        // 已经是其他所选待办子孙的只随祖先移动
+        let subtrees: Vec<Vec<&TodoItem>> = ids.iter().map(|id| self.subtree(id)).collect();
+        let mut roots: Vec<String> = Vec::new();
+        let mut moved: Vec<String> = Vec::new();
+        for (index, subtree) in subtrees.iter().enumerate() {
+            let root = &subtree[0].id;
+            let nested = subtrees.iter().enumerate()
+                .any(|(other, s)| other != index && s[0].id != *root && s.iter().any(|t| &t.id == root));
+            if nested || roots.contains(root) {
+                continue;
+            }
+            roots.push(root.clone());
+            let mut descendants = subtree[1..].to_vec();
+            descendants.sort_by(|a, b| a.order.total_cmp(&b.order));
+            for todo in std::iter::once(subtree[0]).chain(descendants) {
+                if !moved.contains(&todo.id) {
+                    moved.push(todo.id.clone());
+                }
+            }
+        }
+
+        // 父待办没有一起移动且在其他分组时，移动后成为顶层待办
+        let detached: Vec<String> = self.todos.iter()
+            .filter(|t| roots.contains(&t.id))
+            .filter(|t| t.parent_id.as_ref().map(|p| {
+                !self.todos.iter().any(|o| &o.id == p && o.group_id == target_group_id)
+            }).unwrap_or(false))
+            .map(|t| t.id.clone())
+            .collect();
+
+        let now = Utc::now();
+        for todo in self.todos.iter_mut() {
+            if detached.contains(&todo.id) {
+                todo.record_change("parent_id", todo.parent_id.clone(), None);
+                todo.parent_id = None;
+                todo.updated_at = now;
+            }
+            if moved.contains(&todo.id) && todo.group_id != target_group_id {
+                todo.record_change("group_id", Some(todo.group_id.clone()), Some(target_group_id.to_string()));
+                todo.group_id = target_group_id.to_string();
+                let custom_fields = target_group.retain_custom_fields(&todo.custom_fields);
+                if custom_fields != todo.custom_fields {
+                    todo.set_custom_fields(custom_fields);
+                }
+                todo.updated_at = now;
+            }
         }
+
+        // 按父待办分别放入目标位置
+        let mut scopes: Vec<(Option<String>, Vec<String>)> = Vec::new();
+        for root in &roots {
+            let parent_id = self.todos.iter().find(|t| &t.id == root).and_then(|t| t.parent_id.clone());
+            match scopes.iter_mut().find(|(p, _)| *p == parent_id) {
+                Some((_, ids)) => ids.push(root.clone()),
+                None => scopes.push((parent_id, vec![root.clone()])),
+            }
+        }
+        for (parent_id, ids) in scopes {
+            self.insert_into_scope(target_group_id, parent_id.as_deref(), &ids, position);
+        }
+
+        Ok(moved)
+    }
+}
+
+// 依赖关系
+impl AppData {
+    // 仍有未完成的依赖时视为阻塞；已删除的依赖不计
+    pub fn is_blocked(&self, todo: &TodoItem) -> bool {
+        todo.blocked_by.iter().any(|blocker_id| {
+            self.todos.iter().any(|t| &t.id == blocker_id && !t.completed)
+        })
+    }
+
+    // 从 blocker 沿 blocked_by 深度优先搜索，能回到 todo 说明加入依赖后会成环
+    pub fn would_create_cycle(&self, todo_id: &str, blocker_id: &str) -> bool {
+        let mut stack = vec![blocker_id.to_string()];
+        let mut visited: Vec<String> = Vec::new();
+        while let Some(id) = stack.pop() {
+            if id == todo_id {
+                return true;
+            }
+            if visited.contains(&id) {
+                continue;
+            }
+            if let Some(todo) = self.todos.iter().find(|t| t.id == id) {
+                stack.extend(todo.blocked_by.iter().cloned());
+            }
+            visited.push(id);
+        }
+        false
+    }
+
+    // 不能把待办挂到自己或自己的子孙下
+    pub fn would_create_parent_cycle(&self, todo_id: &str, parent_id: &str) -> bool {
+        self.subtree(todo_id).iter().any(|t| t.id == parent_id)
+    }
+
+    // 依赖 blocker 且已不再阻塞的未完成待办
+    pub fn unblocked_by(&self, blocker_id: &str) -> Vec<String> {
+        self.todos.iter()
+            .filter(|t| !t.completed && t.blocked_by.iter().any(|b| b == blocker_id))
+            .filter(|t| !self.is_blocked(t))
+            .map(|t| t.id.clone())
+            .collect()
+    }
+
+    // 删除待办后清理其他待办中指向它的引用附件
+    pub fn remove_todo_refs(&mut self, todo_id: &str) {
+        for todo in self.todos.iter_mut() {
+            let refs: Vec<Attachment> = todo.attachments.iter()
+                .filter(|a| matches!(&a.kind, AttachmentKind::TodoRef { todo_id: id } if id == todo_id))
+                .cloned()
+                .collect();
+            for attachment in refs {
+                todo.record_change("attachment", Some(attachment.label()), None);
+                todo.attachments.retain(|a| a.id != attachment.id);
+                todo.updated_at = Utc::now();
+            }
+        }
+    }
+
+    // 通过依赖、引用或父子关系指向该待办的其他待办，删除它时这些待办也会变化
+    pub fn todos_referencing(&self, todo_id: &str) -> Vec<String> {
+        self.todos.iter()
+            .filter(|t| t.id != todo_id)
+            .filter(|t| {
+                t.parent_id.as_deref() == Some(todo_id)
+                    || t.blocked_by.iter().any(|b| b == todo_id)
+                    || t.attachments.iter().any(|a| matches!(&a.kind, AttachmentKind::TodoRef { todo_id: id } if id == todo_id))
+            })
+            .map(|t| t.id.clone())
+            .collect()
+    }
+
+    // 删除待办前给它的计时记录补上标题和分组（结束计时时已记下的保留）。
+    // 计时记录不随待办删除，todo_id 之后指向已不存在的待办
+    pub fn keep_time_entries(&mut self, todo_ids: &[String]) {
+        for entry in self.time_entries.iter_mut().filter(|e| todo_ids.contains(&e.todo_id)) {
+            if let Some(todo) = self.todos.iter().find(|t| t.id == entry.todo_id) {
+                entry.title.get_or_insert_with(|| todo.title.clone());
+                entry.group_id.get_or_insert_with(|| todo.group_id.clone());
+            }
+        }
+    }
+
+    // 删除待办后清理其他待办中指向它的依赖
+    pub fn remove_dependency_refs(&mut self, blocker_id: &str) {
+        for todo in self.todos.iter_mut() {
+            if todo.blocked_by.iter().any(|b| b == blocker_id) {
+                todo.record_change("blocked_by", Some(blocker_id.to_string()), None);
+                todo.blocked_by.retain(|b| b != blocker_id);
+                todo.updated_at = Utc::now();
+            }
+        }
+    }
+}
+
+// 完成依赖后推送给前端的事件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodosUnblocked {
+    pub blocker_id: String,
+    pub todo_ids: Vec<String>,
+}
+
+// 数据变更后推送给前端的事件内容，前端只需重新获取涉及的待办和分组；
+// 查不到的 id 表示已被删除，reload 为 true 时需要重新加载全部数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataChanged {
+    pub todo_ids: Vec<String>,
+    pub group_ids: Vec<String>,
+    pub reload: bool,
+}
+
+// 计时记录，end 为空表示正在计时（持久化后重启仍可继续）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub id: String,
+    pub todo_id: String,
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+    pub note: Option<String>,
+    // 结束计时时的待办标题和分组，待办删除后汇总仍按它们计算
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub group_id: Option<String>,
+}
+
+impl TimeEntry {
+    pub fn seconds(&self, now: DateTime<Utc>) -> i64 {
+        (self.end.unwrap_or(now) - self.start).num_seconds().max(0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeTotal {
+    // 待办 id、分组 id 或本地日期（YYYY-MM-DD）
+    pub key: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimeTotals {
+    pub by_todo: Vec<TimeTotal>,
+    pub by_group: Vec<TimeTotal>,
+    pub by_day: Vec<TimeTotal>,
+}
+
+fn add_seconds(totals: &mut Vec<TimeTotal>, key: &str, seconds: i64) {
+    match totals.iter_mut().find(|t| t.key == key) {
+        Some(total) => total.seconds += seconds,
+        None => totals.push(TimeTotal { key: key.to_string(), seconds }),
+    }
+}
+
+// 计时
+impl AppData {
+    pub fn running_timer(&self) -> Option<&TimeEntry> {
+        self.time_entries.iter().find(|e| e.end.is_none())
+    }
+
+    // 结束正在运行的计时，返回被结束的记录
+    pub fn stop_running_timer(&mut self, now: DateTime<Utc>) -> Option<TimeEntry> {
+        let entry = self.time_entries.iter_mut().find(|e| e.end.is_none())?;
+        entry.end = Some(now);
+        if let Some(todo) = self.todos.iter().find(|t| t.id == entry.todo_id) {
+            entry.title = Some(todo.title.clone());
+            entry.group_id = Some(todo.group_id.clone());
+        }
+        Some(entry.clone())
+    }
+
+    // 按待办、分组和本地日期汇总；只统计落在 [from, to) 内的部分，跨天的记录按天拆分
+    pub fn time_totals(&self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> TimeTotals {
+        let now = Utc::now();
+        let mut totals = TimeTotals::default();
+
+        for entry in &self.time_entries {
+            let start = from.map(|f| entry.start.max(f)).unwrap_or(entry.start);
+            let end = entry.end.unwrap_or(now);
+            let end = to.map(|t| end.min(t)).unwrap_or(end);
+            if end <= start {
+                continue;
+            }
+
+            let seconds = (end - start).num_seconds();
+            add_seconds(&mut totals.by_todo, &entry.todo_id, seconds);
+            let group_id = self.todos.iter()
+                .find(|t| t.id == entry.todo_id)
+                .map(|t| t.group_id.as_str())
+                .or(entry.group_id.as_deref());
+            if let Some(group_id) = group_id {
+                add_seconds(&mut totals.by_group, group_id, seconds);
+            }
+
+            let mut day_start = start;
+            while day_start < end {
+                let date = day_start.with_timezone(&Local).date_naive();
+                let next_day = date.succ_opt()
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .and_then(|d| Local.from_local_datetime(&d).earliest())
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or(day_start + Duration::days(1));
+                let day_end = next_day.min(end);
+                add_seconds(&mut totals.by_day, &date.format("%Y-%m-%d").to_string(), (day_end - day_start).num_seconds());
+                day_start = day_end;
+            }
+        }
+
+        totals.by_todo.sort_by_key(|t| Reverse(t.seconds));
+        totals.by_group.sort_by_key(|t| Reverse(t.seconds));
+        totals.by_day.sort_by(|a, b| a.key.cmp(&b.key));
+        totals
+    }
+}
+
+// 待办及其所有子待办的工作量汇总（分钟）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EffortRollup {
+    pub todo_id: String,
+    pub estimate_minutes: u32,
+    // 未完成、未归档部分的预估
+    pub remaining_minutes: u32,
+    pub tracked_minutes: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupEffortSummary {
+    pub group_id: String,
+    pub estimate_minutes: u32,
+    pub remaining_minutes: u32,
+    pub tracked_minutes: i64,
+    pub open_todos: usize,
+    // 未完成但没有预估的待办数，剩余工作量可能被低估
+    pub unestimated_todos: usize,
+}
+
+fn remaining_estimate(todo: &TodoItem) -> u32 {
+    if todo.completed || todo.archived {
+        0
+    } else {
+        todo.estimate_minutes.unwrap_or(0)
+    }
+}
+
+// 工作量
+impl AppData {
+    // 待办本身及经 parent_id 连接的所有后代（防止 parent_id 成环）
+    pub fn subtree(&self, todo_id: &str) -> Vec<&TodoItem> {
+        let mut result: Vec<&TodoItem> = Vec::new();
+        let mut stack = vec![todo_id];
+        while let Some(id) = stack.pop() {
+            if result.iter().any(|t| t.id == id) {
+                continue;
+            }
+            if let Some(todo) = self.todos.iter().find(|t| t.id == id) {
+                result.push(todo);
+                stack.extend(self.todos.iter()
+                    .filter(|t| t.parent_id.as_deref() == Some(id))
+                    .map(|t| t.id.as_str()));
+            }
+        }
+        result
+    }
+
+    fn tracked_seconds(&self, todo_ids: &[&str], now: DateTime<Utc>) -> i64 {
+        self.time_entries.iter()
+            .filter(|e| todo_ids.contains(&e.todo_id.as_str()))
+            .map(|e| e.seconds(now))
+            .sum()
+    }
+
+    pub fn effort_rollup(&self, todo_id: &str) -> Option<EffortRollup> {
+        let subtree = self.subtree(todo_id);
+        if subtree.is_empty() {
+            return None;
+        }
+        let ids: Vec<&str> = subtree.iter().map(|t| t.id.as_str()).collect();
+
+        Some(EffortRollup {
+            todo_id: todo_id.to_string(),
+            estimate_minutes: subtree.iter().filter_map(|t| t.estimate_minutes).sum(),
+            remaining_minutes: subtree.iter().map(|t| remaining_estimate(t)).sum(),
+            tracked_minutes: self.tracked_seconds(&ids, Utc::now()) / 60,
+        })
+    }
+
+    // 按待办自身的 group_id 统计，子待办不会重复计入
+    pub fn group_effort_summary(&self, group_id: &str, include_subgroups: bool) -> GroupEffortSummary {
+        let group_ids = if include_subgroups {
+            self.group_subtree(group_id)
+        } else {
+            vec![group_id.to_string()]
+        };
+        let todos: Vec<&TodoItem> = self.todos.iter().filter(|t| group_ids.contains(&t.group_id)).collect();
+        let ids: Vec<&str> = todos.iter().map(|t| t.id.as_str()).collect();
+        let open: Vec<&&TodoItem> = todos.iter().filter(|t| !t.completed && !t.archived).collect();
+
+        GroupEffortSummary {
+            group_id: group_id.to_string(),
+            estimate_minutes: todos.iter().filter(|t| !t.archived).filter_map(|t| t.estimate_minutes).sum(),
+            remaining_minutes: open.iter().map(|t| remaining_estimate(t)).sum(),
+            tracked_minutes: self.tracked_seconds(&ids, Utc::now()) / 60,
+            open_todos: open.len(),
+            unestimated_todos: open.iter().filter(|t| t.estimate_minutes.is_none()).count(),
+        }
+    }
+}
+
+// 模板中的时间节点，offset 相对实例化时的基准日期，如 "+2d 09:00"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateTimeNode {
+    pub offset: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub reminder_enabled: bool,
+    #[serde(default)]
+    pub reminder_minutes_before: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateItem {
+    pub title: String,
+    #[serde(default)]
+    pub details: Option<String>,
+    #[serde(default)]
+    pub color_tag: ColorTag,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    #[serde(default)]
+    pub start_offset: Option<String>,
+    #[serde(default)]
+    pub due_offset: Option<String>,
+    #[serde(default)]
+    pub time_nodes: Vec<TemplateTimeNode>,
+    // 实例化到其他分组时只保留目标分组中有效的字段
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
+    #[serde(default)]
+    pub children: Vec<TemplateItem>,
+}
+
+// 可重复实例化的待办树
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoTemplate {
+    pub id: String,
+    pub name: String,
+    pub items: Vec<TemplateItem>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 复制待办的选项；date_offset 使用模板相同的偏移写法，如 "+7d"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DuplicateOptions {
+    pub include_children: bool,
+    pub date_offset: Option<String>,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        DuplicateOptions {
+            include_children: true,
+            date_offset: None,
+        }
+    }
+}
+
+// 一次番茄钟专注；中途停止的记为未完成
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusSession {
+    pub id: String,
+    pub todo_id: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FocusStats {
+    pub completed_pomodoros: u32,
+    pub interrupted_pomodoros: u32,
+    pub focus_seconds: i64,
+    pub by_todo: Vec<TimeTotal>,
+    pub by_day: Vec<TimeTotal>,
+}
+
+impl AppData {
+    // 按开始时间筛选专注记录并汇总
+    pub fn focus_stats(&self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> FocusStats {
+        let mut stats = FocusStats::default();
+        let sessions = self.focus_sessions.iter()
+            .filter(|s| from.map(|f| s.start >= f).unwrap_or(true))
+            .filter(|s| to.map(|t| s.start < t).unwrap_or(true));
+
+        for session in sessions {
+            if session.completed {
+                stats.completed_pomodoros += 1;
+            } else {
+                stats.interrupted_pomodoros += 1;
+            }
+            let seconds = (session.end - session.start).num_seconds().max(0);
+            stats.focus_seconds += seconds;
+            if let Some(todo_id) = &session.todo_id {
+                add_seconds(&mut stats.by_todo, todo_id, seconds);
+            }
+            let day = session.start.with_timezone(&Local).format("%Y-%m-%d").to_string();
+            add_seconds(&mut stats.by_day, &day, seconds);
+        }
+
+        stats.by_todo.sort_by_key(|t| Reverse(t.seconds));
+        stats.by_day.sort_by(|a, b| a.key.cmp(&b.key));
+        stats
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub todos_imported: usize,
+    pub groups_created: usize,
+    // 无法映射、被忽略或降级处理的内容说明
+    pub unmapped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DateField {
+    Created,
+    Updated,
+    Completed,
+}
+
+// 待办筛选条件，空条件表示不限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TodoFilter {
+    pub group_ids: Option<Vec<String>>,
+    pub include_archived: bool,
+    pub include_completed: bool,
+    pub date_field: DateField,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    // 需同时包含所有列出的标签
+    pub tags: Option<Vec<String>>,
+    // group_ids 是否包含子孙分组
+    pub include_subgroups: bool,
+}
+
+impl Default for TodoFilter {
+    fn default() -> Self {
+        TodoFilter {
+            group_ids: None,
+            include_archived: true,
+            include_completed: true,
+            date_field: DateField::Created,
+            from: None,
+            to: None,
+            tags: None,
+            include_subgroups: false,
+        }
+    }
+}
+
+impl TodoFilter {
+    // 不排除任何待办
+    pub fn is_unrestricted(&self) -> bool {
+        self.group_ids.is_none()
+            && self.include_archived
+            && self.include_completed
+            && self.from.is_none()
+            && self.to.is_none()
+            && self.tags.as_ref().map(|tags| tags.is_empty()).unwrap_or(true)
+    }
+
+    pub fn matches(&self, todo: &TodoItem) -> bool {
+        if let Some(group_ids) = &self.group_ids {
+            if !group_ids.contains(&todo.group_id) {
+                return false;
+            }
+        }
+        if (todo.archived && !self.include_archived) || (todo.completed && !self.include_completed) {
+            return false;
+        }
+        if let Some(tags) = &self.tags {
+            if !tags.iter().all(|tag| todo.tags.contains(tag)) {
+                return false;
+            }
+        }
+
+        if self.from.is_none() && self.to.is_none() {
+            return true;
+        }
+        // 按完成时间筛选时，未完成的待办不在任何时间范围内
+        let date = match self.date_field {
+            DateField::Created => Some(todo.created_at),
+            DateField::Updated => Some(todo.updated_at),
+            DateField::Completed => todo.completed_at,
+        };
+        date.map(|date| {
+            self.from.map(|from| date >= from).unwrap_or(true)
+                && self.to.map(|to| date <= to).unwrap_or(true)
+        }).unwrap_or(false)
+    }
+}
+
+// 归档待办的一页，page 从 1 开始，total 为符合条件的总数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedPage {
+    pub todos: Vec<TodoItem>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub filter: TodoFilter,
+    pub include_settings: bool,
+}
+
+// 导出文件内容；不含设置时 settings 省略，导入时保留本机设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedData {
+    pub todos: Vec<TodoItem>,
+    pub groups: Vec<TodoGroup>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<AppSettings>,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    pub focus_sessions: Vec<FocusSession>,
+    #[serde(default)]
+    pub templates: Vec<TodoTemplate>,
+    #[serde(default)]
+    pub version: u32,
+    // 按条件导出的部分数据，只能合并导入，不能替换本机数据
+    #[serde(default)]
+    pub partial: bool,
+}
+
+// 导入前预览导出文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportInfo {
+    pub partial: bool,
+    pub todos: usize,
+    pub groups: usize,
+}
+
+impl ExportedData {
+    pub fn migrate(&mut self) {
+        if self.version < DATA_VERSION {
+            migrate_todos(&mut self.todos, self.version);
+            self.version = DATA_VERSION;
+        }
+    }
+}
+
+impl AppData {
+    pub fn export_selection(&self, options: &ExportOptions) -> ExportedData {
+        let filter = self.expand_group_filter(&options.filter);
+        let mut todos: Vec<TodoItem> = self.todos.iter()
+            .filter(|t| filter.matches(t))
+            .cloned()
+            .collect();
+
+        // 父待办未被导出时，子待办作为顶层待办；未导出的依赖一并去掉
+        let ids: Vec<String> = todos.iter().map(|t| t.id.clone()).collect();
+        for todo in todos.iter_mut() {
+            if todo.parent_id.as_ref().map(|p| !ids.contains(p)).unwrap_or(false) {
+                todo.parent_id = None;
+            }
+            todo.blocked_by.retain(|b| ids.contains(b));
+        }
+
+        let mut groups: Vec<TodoGroup> = self.groups.iter()
+            .filter(|g| match &filter.group_ids {
+                Some(group_ids) => group_ids.contains(&g.id),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        // 上级分组未被导出时作为顶层分组
+        let group_ids: Vec<String> = groups.iter().map(|g| g.id.clone()).collect();
+        for group in groups.iter_mut() {
+            if group.parent_group_id.as_ref().map(|p| !group_ids.contains(p)).unwrap_or(false) {
+                group.parent_group_id = None;
+            }
+        }
+
+        // 模板、未关联待办的专注记录和已删除待办的计时记录不属于任何分组，只在完整导出时包含
+        let partial = !filter.is_unrestricted();
+        let time_entries = self.time_entries.iter()
+            .filter(|e| ids.contains(&e.todo_id) || (!partial && !self.todos.iter().any(|t| t.id == e.todo_id)))
+            .cloned()
+            .collect();
+        let focus_sessions = self.focus_sessions.iter()
+            .filter(|s| s.todo_id.as_ref().map(|id| ids.contains(id)).unwrap_or(!partial))
+            .cloned()
+            .collect();
+        let templates = if partial { Vec::new() } else { self.templates.clone() };
+
+        ExportedData {
+            todos,
+            groups,
+            settings: if options.include_settings { Some(self.settings.clone()) } else { None },
+            time_entries,
+            focus_sessions,
+            templates,
+            version: self.version,
+            partial,
+        }
+    }
+
+    // 按 id 合并导入的分组和待办：已有的覆盖，新的追加
+    pub fn merge(&mut self, imported: ExportedData) {
+        for group in imported.groups {
+            match self.groups.iter_mut().find(|g| g.id == group.id) {
+                Some(existing) => {
+                    existing.name = group.name;
+                }
+                None => {
+                    let mut group = group;
+                    group.is_default = false;
+                    group.order = self.next_group_order(group.parent_group_id.as_deref());
+                    self.groups.push(group);
+                }
+            }
+        }
+        // 上级分组不存在的作为顶层分组
+        let group_ids: Vec<String> = self.groups.iter().map(|g| g.id.clone()).collect();
+        for group in self.groups.iter_mut() {
+            if group.parent_group_id.as_ref().map(|p| !group_ids.contains(p)).unwrap_or(false) {
+                group.parent_group_id = None;
+            }
+        }
+        for todo in imported.todos {
+            match self.todos.iter_mut().find(|t| t.id == todo.id) {
+                Some(existing) => *existing = todo,
+                None => self.todos.push(todo),
+            }
+        }
+        for entry in imported.time_entries {
+            match self.time_entries.iter_mut().find(|e| e.id == entry.id) {
+                Some(existing) => *existing = entry,
+                None => self.time_entries.push(entry),
+            }
+        }
+        for template in imported.templates {
+            match self.templates.iter_mut().find(|t| t.id == template.id) {
+                Some(existing) => *existing = template,
+                None => self.templates.push(template),
+            }
+        }
+        for session in imported.focus_sessions {
+            if !self.focus_sessions.iter().any(|s| s.id == session.id) {
+                self.focus_sessions.push(session);
+            }
+        }
+        // 只保留一个正在运行的计时
+        if self.time_entries.iter().filter(|e| e.end.is_none()).count() > 1 {
+            self.stop_running_timer(Utc::now());
+        }
+        if let Some(settings) = imported.settings {
+            self.settings = settings;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_with(titles: &[&str]) -> AppData {
+        let mut data = AppData::default();
+        let group_id = data.default_group_id();
+        for (index, title) in titles.iter().enumerate() {
+            data.todos.push(TodoItem::new(title.to_string(), group_id.clone(), index as f64));
+        }
+        data
+    }
+
+    fn id_of(data: &AppData, title: &str) -> String {
+        data.todos.iter().find(|t| t.title == title).unwrap().id.clone()
+    }
+
+    // 分组内某个父待办下按 order 排列的标题
+    fn sibling_titles(data: &AppData, group_id: &str, parent_id: Option<&str>) -> Vec<String> {
+        let mut siblings: Vec<&TodoItem> = data.todos.iter()
+            .filter(|t| t.group_id == group_id && t.parent_id.as_deref() == parent_id)
+            .collect();
+        siblings.sort_by(|a, b| a.order.total_cmp(&b.order));
+        siblings.iter().map(|t| t.title.clone()).collect()
+    }
+
+    #[test]
+    fn dependency_cycles_are_detected() {
+        let mut data = data_with(&["a", "b", "c"]);
+        let (a, b, c) = (id_of(&data, "a"), id_of(&data, "b"), id_of(&data, "c"));
+        // c 依赖 b，b 依赖 a
+        data.todos[1].blocked_by.push(a.clone());
+        data.todos[2].blocked_by.push(b.clone());
+
+        assert!(data.would_create_cycle(&a, &c));
+        assert!(data.would_create_cycle(&a, &a));
+        assert!(!data.would_create_cycle(&c, &a));
+    }
+
+    #[test]
+    fn parent_cycles_are_detected() {
+        let mut data = data_with(&["root", "child", "grandchild", "other"]);
+        let (root, child, grandchild, other) =
+            (id_of(&data, "root"), id_of(&data, "child"), id_of(&data, "grandchild"), id_of(&data, "other"));
+        data.todos[1].parent_id = Some(root.clone());
+        data.todos[2].parent_id = Some(child.clone());
+
+        assert!(data.would_create_parent_cycle(&root, &grandchild));
+        assert!(data.would_create_parent_cycle(&root, &root));
+        assert!(!data.would_create_parent_cycle(&grandchild, &root));
+        assert!(!data.would_create_parent_cycle(&root, &other));
+    }
+
+    #[test]
+    fn orders_between_neighbours() {
+        assert_eq!(orders_between(Some(1.0), Some(2.0), 1), Some(vec![1.5]));
+        assert_eq!(orders_between(None, Some(0.0), 1), Some(vec![-1.0]));
+        assert_eq!(orders_between(Some(3.0), None, 2), Some(vec![4.0, 5.0]));
+        assert_eq!(orders_between(None, None, 2), Some(vec![0.0, 1.0]));
+
+        let orders = orders_between(Some(0.0), Some(1.0), 3).unwrap();
+        assert!(orders.windows(2).all(|w| w[0] < w[1]));
+        assert!(orders[0] > 0.0 && orders[2] < 1.0);
+
+        let next = f64::from_bits(1.0f64.to_bits() + 1);
+        assert_eq!(orders_between(Some(1.0), Some(next), 1), None);
+    }
+
+    #[test]
+    fn place_between_before_and_after_siblings() {
+        let mut data = data_with(&["a", "b", "c"]);
+        let group_id = data.default_group_id();
+        let (a, c) = (id_of(&data, "a"), id_of(&data, "c"));
+
+        // 移到 a 之后只改 c 本身
+        data.place_todo_after(&c, Some(&a)).unwrap();
+        assert_eq!(sibling_titles(&data, &group_id, None), vec!["a", "c", "b"]);
+        assert_eq!(data.todos.iter().map(|t| t.order).collect::<Vec<_>>(), vec![0.0, 1.0, 0.5]);
+
+        data.place_todo_after(&a, Some(&id_of(&data, "b"))).unwrap();
+        assert_eq!(sibling_titles(&data, &group_id, None), vec!["c", "b", "a"]);
+        assert_eq!(data.todos[0].order, 2.0);
+
+        data.place_todo_after(&a, None).unwrap();
+        assert_eq!(sibling_titles(&data, &group_id, None), vec!["a", "c", "b"]);
+        assert_eq!(data.todos[0].order, -0.5);
+
+        let other = data_with(&["x"]);
+        assert!(data.place_todo_after(&a, Some(&other.todos[0].id)).is_err());
+    }
+
+    #[test]
+    fn insert_renumbers_when_gap_runs_out() {
+        let mut data = data_with(&["a", "b", "c"]);
+        let group_id = data.default_group_id();
+        data.todos[1].order = f64::from_bits(0.0f64.to_bits() + 1);
+        let c = id_of(&data, "c");
+
+        data.insert_into_scope(&group_id, None, &[c], Some(1));
+        assert_eq!(sibling_titles(&data, &group_id, None), vec!["a", "c", "b"]);
+        assert_eq!(data.todos.iter().map(|t| t.order).collect::<Vec<_>>(), vec![0.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn move_subtree_across_groups() {
+        let mut data = data_with(&["a0", "parent", "child", "a3"]);
+        let source = data.default_group_id();
+        let target_group = TodoGroup::new("Target".to_string(), None, 1);
+        let target = target_group.id.clone();
+        data.groups.push(target_group);
+        for (index, title) in ["b0", "b1"].iter().enumerate() {
+            data.todos.push(TodoItem::new(title.to_string(), target.clone(), index as f64));
+        }
+        let (parent, child) = (id_of(&data, "parent"), id_of(&data, "child"));
+        data.todos[2].parent_id = Some(parent.clone());
+        data.todos[2].order = 0.0;
+
+        // 子待办随父待办移动，重复选中不会移动两次
+        let moved = data.move_todos(&[child.clone(), parent.clone()], &target, Some(1)).unwrap();
+        assert_eq!(moved, vec![parent.clone(), child.clone()]);
+
+        assert_eq!(sibling_titles(&data, &target, None), vec!["b0", "parent", "b1"]);
+        assert_eq!(sibling_titles(&data, &target, Some(&parent)), vec!["child"]);
+        assert_eq!(sibling_titles(&data, &source, None), vec!["a0", "a3"]);
+        let moved_child = data.todos.iter().find(|t| t.id == child).unwrap();
+        assert_eq!(moved_child.group_id, target);
+        assert_eq!(moved_child.parent_id.as_ref(), Some(&parent));
+
+        // 只移动子待办时脱离留在原分组的父待办
+        let mut data = data_with(&["parent", "child"]);
+        let target_group = TodoGroup::new("Target".to_string(), None, 1);
+        let target = target_group.id.clone();
+        data.groups.push(target_group);
+        let (parent, child) = (id_of(&data, "parent"), id_of(&data, "child"));
+        data.todos[1].parent_id = Some(parent);
+        data.move_todos(std::slice::from_ref(&child), &target, None).unwrap();
+        assert_eq!(data.todos[1].parent_id, None);
+        assert_eq!(sibling_titles(&data, &target, None), vec!["child"]);
+
+        assert!(data.move_todos(&[child], "missing", None).is_err());
     }
 }
@@ -0,0 +1,194 @@
+use crate::csv::parse_date_time;
+use crate::models::*;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Deserialize;
+
+// 兼容直接的列表数组，以及 Graph API 的 { "value": [...] } 包装
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MsTodoExport {
+    Lists(Vec<MsTodoList>),
+    Wrapped {
+        #[serde(alias = "value")]
+        lists: Vec<MsTodoList>,
+    },
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct MsTodoList {
+    display_name: String,
+    tasks: Vec<MsTodoTask>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct MsTodoTask {
+    title: String,
+    body: Option<MsTodoBody>,
+    importance: String,
+    status: String,
+    created_date_time: Option<String>,
+    last_modified_date_time: Option<String>,
+    completed_date_time: Option<MsDateTime>,
+    due_date_time: Option<MsDateTime>,
+    is_reminder_on: bool,
+    reminder_date_time: Option<MsDateTime>,
+    checklist_items: Vec<MsChecklistItem>,
+    recurrence: Option<serde_json::Value>,
+    categories: Vec<String>,
+    linked_resources: Vec<serde_json::Value>,
+    has_attachments: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct MsTodoBody {
+    content: String,
+    content_type: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct MsDateTime {
+    date_time: String,
+    time_zone: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct MsChecklistItem {
+    display_name: String,
+    is_checked: bool,
+    created_date_time: Option<String>,
+    checked_date_time: Option<String>,
+}
+
+fn color_tag_from_importance(importance: &str) -> ColorTag {
+    match importance {
+        "high" => ColorTag::Red1,
+        "low" => ColorTag::Purple7,
+        _ => ColorTag::default(),
+    }
+}
+
+// UTC 时间直接解析；其他 Windows 时区名无法换算，按本地时间处理
+fn parse_ms_date_time(value: &MsDateTime) -> Option<DateTime<Utc>> {
+    if value.time_zone.is_empty() || value.time_zone.eq_ignore_ascii_case("UTC") {
+        NaiveDateTime::parse_from_str(&value.date_time, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .map(|d| d.and_utc())
+            .or_else(|| parse_date_time(&value.date_time))
+    } else {
+        parse_date_time(&value.date_time)
+    }
+}
+
+/// 导入 Microsoft To Do（Graph API 格式）导出的 JSON。
+pub fn import_json(content: &str, data: &mut AppData) -> Result<ImportSummary, String> {
+    let export: MsTodoExport = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse Microsoft To Do file: {}", e))?;
+    let lists = match export {
+        MsTodoExport::Lists(lists) => lists,
+        MsTodoExport::Wrapped { lists } => lists,
+    };
+
+    let mut summary = ImportSummary::default();
+    let mut local_time_zones = false;
+
+    for list in lists {
+        let (group_id, created) = data.find_or_create_group(&list.display_name);
+        if created {
+            summary.groups_created += 1;
+        }
+
+        for task in list.tasks {
+            let order = data.next_todo_order(&group_id, None);
+            let mut todo = TodoItem::new(task.title.clone(), group_id.clone(), order);
+            if let Some(body) = task.body.as_ref().filter(|b| !b.content.trim().is_empty()) {
+                todo.details = Some(body.content.trim().to_string());
+                if body.content_type.eq_ignore_ascii_case("html") {
+                    summary.unmapped.push(format!("\"{}\": HTML notes imported as plain text", task.title));
+                }
+            }
+            todo.color_tag = color_tag_from_importance(&task.importance);
+            todo.priority = Priority::from(todo.color_tag);
+            todo.tags = normalize_tags(task.categories.clone());
+            todo.completed = task.status == "completed";
+            if let Some(created_at) = task.created_date_time.as_deref().and_then(parse_date_time) {
+                todo.created_at = created_at;
+            }
+            todo.updated_at = task.last_modified_date_time.as_deref()
+                .and_then(parse_date_time)
+                .unwrap_or(todo.created_at);
+            if todo.completed {
+                todo.completed_at = task.completed_date_time.as_ref()
+                    .and_then(parse_ms_date_time)
+                    .or(Some(todo.updated_at));
+            }
+
+            for date_time in [&task.due_date_time, &task.reminder_date_time].into_iter().flatten() {
+                if !date_time.time_zone.is_empty() && !date_time.time_zone.eq_ignore_ascii_case("UTC") {
+                    local_time_zones = true;
+                }
+            }
+
+            let due = task.due_date_time.as_ref().and_then(parse_ms_date_time);
+            todo.due_date = due;
+            if let Some(date_time) = due {
+                todo.time_nodes.push(TimeNode::new(date_time, Some("due".to_string())));
+            }
+            if task.is_reminder_on {
+                match (task.reminder_date_time.as_ref().and_then(parse_ms_date_time), due) {
+                    // 提醒早于截止时间时，折算为截止节点的提前提醒
+                    (Some(reminder), Some(due)) if reminder <= due => {
+                        todo.time_nodes[0].reminder_enabled = true;
+                        todo.time_nodes[0].reminder_minutes_before = (due - reminder).num_minutes() as i32;
+                    }
+                    (Some(reminder), _) => {
+                        let mut node = TimeNode::new(reminder, Some("reminder".to_string()));
+                        node.reminder_enabled = true;
+                        todo.time_nodes.push(node);
+                    }
+                    (None, _) => summary.unmapped.push(format!("\"{}\": reminder without a date ignored", task.title)),
+                }
+            }
+
+            if task.recurrence.is_some() {
+                summary.unmapped.push(format!("\"{}\": recurrence ignored", task.title));
+            }
+            if !task.linked_resources.is_empty() || task.has_attachments {
+                summary.unmapped.push(format!("\"{}\": attachments and linked resources ignored", task.title));
+            }
+
+            // 步骤（checklist）作为子待办
+            let parent_id = todo.id.clone();
+            data.todos.push(todo);
+            summary.todos_imported += 1;
+
+            for item in task.checklist_items {
+                let order = data.next_todo_order(&group_id, Some(&parent_id));
+                let mut child = TodoItem::new(item.display_name, group_id.clone(), order);
+                child.parent_id = Some(parent_id.clone());
+                if let Some(created_at) = item.created_date_time.as_deref().and_then(parse_date_time) {
+                    child.created_at = created_at;
+                    child.updated_at = created_at;
+                }
+                if item.is_checked {
+                    child.completed = true;
+                    child.completed_at = item.checked_date_time.as_deref()
+                        .and_then(parse_date_time)
+                        .or(Some(child.updated_at));
+                }
+                data.todos.push(child);
+                summary.todos_imported += 1;
+            }
+        }
+    }
+
+    if local_time_zones {
+        summary.unmapped.push("Non-UTC time zones were interpreted as local time".to_string());
+    }
+
+    Ok(summary)
+}
@@ -0,0 +1,130 @@
+use crate::commands::{self, AppState};
+use crate::models::*;
+use crate::pomodoro::{self, PomodoroPhase, PomodoroStatus};
+use chrono::{DateTime, Duration, Local, Utc};
+use std::thread;
+use tauri::api::notification::Notification;
+use tauri::{AppHandle, Manager};
+
+const TICK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// 发送系统通知，时间节点提醒和番茄钟共用。
+pub fn notify(app: &AppHandle, title: &str, body: &str) {
+    let identifier = app.config().tauri.bundle.identifier.clone();
+    if let Err(e) = Notification::new(identifier).title(title).body(body).show() {
+        eprintln!("Failed to show notification: {}", e);
+    }
+}
+
+/// 启动后台线程，每秒检查到期的提醒并推进番茄钟。
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || {
+        // 只提醒启动之后到期的节点，关闭期间错过的不补发
+        let mut last_check = Utc::now();
+        loop {
+            thread::sleep(TICK_INTERVAL);
+            let now = Utc::now();
+            check_reminders(&app, last_check, now);
+            check_pomodoro(&app, now);
+            last_check = now;
+        }
+    });
+}
+
+// 提醒时间落在 (since, now] 内的时间节点
+fn check_reminders(app: &AppHandle, since: DateTime<Utc>, now: DateTime<Utc>) {
+    let state = app.state::<AppState>();
+    let due: Vec<(String, String)> = match state.data.lock() {
+        Ok(data) => data.todos.iter()
+            .filter(|t| !t.completed && !t.archived)
+            .flat_map(|t| t.time_nodes.iter().map(move |tn| (t, tn)))
+            .filter(|(_, tn)| tn.reminder_enabled)
+            .filter(|(_, tn)| {
+                let remind_at = tn.date_time - Duration::minutes(tn.reminder_minutes_before as i64);
+                remind_at > since && remind_at <= now
+            })
+            .map(|(t, tn)| {
+                let time = tn.date_time.with_timezone(&Local).format("%H:%M").to_string();
+                let body = match tn.description.as_deref().filter(|d| !d.is_empty()) {
+                    Some(description) => format!("{} {}", time, description),
+                    None => time,
+                };
+                (t.title.clone(), body)
+            })
+            .collect(),
+        Err(e) => {
+            eprintln!("Failed to lock state: {}", e);
+            return;
+        }
+    };
+
+    for (title, body) in due {
+        notify(app, &title, &body);
+    }
+}
+
+fn check_pomodoro(app: &AppHandle, now: DateTime<Utc>) {
+    let state = app.state::<AppState>();
+    // 加锁顺序与命令保持一致：先 data 后 pomodoro
+    let Ok(mut data) = state.data.lock() else {
+        return;
+    };
+    let Ok(mut engine) = state.pomodoro.lock() else {
+        return;
+    };
+
+    let settings = data.settings.pomodoro.clone();
+    let Some(finished) = engine.tick(&settings, now) else {
+        return;
+    };
+    let next = engine.status();
+    drop(engine);
+
+    if finished.phase == PomodoroPhase::Work {
+        pomodoro::record_focus_session(&mut data, &finished, now, true);
+        if let Err(e) = state.storage.save(&data) {
+            eprintln!("{}", e);
+        }
+        // 完成的番茄数记在待办上
+        if let Some(todo_id) = &finished.todo_id {
+            commands::emit_data_changed(app, vec![todo_id.clone()], Vec::new());
+        }
+    }
+
+    let todo_title = finished.todo_id.as_ref()
+        .and_then(|id| data.todos.iter().find(|t| &t.id == id))
+        .map(|t| t.title.clone());
+    let (title, body) = pomodoro_message(data.settings.language, &finished, next.as_ref(), todo_title);
+    drop(data);
+
+    notify(app, &title, &body);
+    pomodoro::emit_status(app, next);
+}
+
+fn pomodoro_message(
+    language: Language,
+    finished: &PomodoroStatus,
+    next: Option<&PomodoroStatus>,
+    todo_title: Option<String>,
+) -> (String, String) {
+    let break_minutes = next.map(|s| (s.ends_at - s.started_at).num_minutes()).unwrap_or(0);
+    let todo_title = todo_title.unwrap_or_default();
+
+    match (finished.phase, language) {
+        (PomodoroPhase::Work, Language::SimplifiedChinese) => (
+            "番茄完成".to_string(),
+            format!("{} 休息 {} 分钟", todo_title, break_minutes).trim().to_string(),
+        ),
+        (PomodoroPhase::Work, Language::TraditionalChinese) => (
+            "番茄完成".to_string(),
+            format!("{} 休息 {} 分鐘", todo_title, break_minutes).trim().to_string(),
+        ),
+        (PomodoroPhase::Work, Language::English) => (
+            "Pomodoro complete".to_string(),
+            format!("{} Take a {}-minute break", todo_title, break_minutes).trim().to_string(),
+        ),
+        (_, Language::SimplifiedChinese) => ("休息结束".to_string(), "开始下一个番茄吧".to_string()),
+        (_, Language::TraditionalChinese) => ("休息結束".to_string(), "開始下一個番茄吧".to_string()),
+        (_, Language::English) => ("Break over".to_string(), "Ready for the next pomodoro".to_string()),
+    }
+}
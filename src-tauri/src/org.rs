@@ -0,0 +1,185 @@
+use crate::models::*;
+use chrono::{DateTime, Local, Utc};
+use std::collections::HashSet;
+
+// 这些描述的时间节点导出为 DEADLINE，其余最早的一个导出为 SCHEDULED
+const DEADLINE_DESCRIPTIONS: [&str; 4] = ["due", "deadline", "截止", "截止日期"];
+
+fn active_timestamp(date_time: &DateTime<Utc>) -> String {
+    date_time.with_timezone(&Local).format("<%Y-%m-%d %a %H:%M>").to_string()
+}
+
+fn inactive_timestamp(date_time: &DateTime<Utc>) -> String {
+    date_time.with_timezone(&Local).format("[%Y-%m-%d %a %H:%M]").to_string()
+}
+
+fn is_deadline(node: &TimeNode) -> bool {
+    node.description.as_deref()
+        .map(|d| DEADLINE_DESCRIPTIONS.contains(&d.trim().to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// 正文中以 * 开头的行会被 Org 当成标题，需要缩进
+fn body_line(line: &str) -> String {
+    if line.starts_with('*') {
+        format!(" {}", line)
+    } else {
+        line.to_string()
+    }
+}
+
+// Org 标签只允许字母数字和 _@#%
+fn org_tag(tag: &str) -> String {
+    tag.chars()
+        .map(|c| if c.is_alphanumeric() || "_@#%".contains(c) { c } else { '_' })
+        .collect()
+}
+
+// 属性名不能含空白
+fn property_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+fn custom_field_value(field: &CustomFieldDef, value: &CustomFieldValue) -> String {
+    match (&field.field_type, value) {
+        (CustomFieldType::Date, CustomFieldValue::Text(text)) => DateTime::parse_from_rfc3339(text)
+            .map(|d| inactive_timestamp(&d.with_timezone(&Utc)))
+            .unwrap_or_else(|_| text.clone()),
+        _ => value.display(),
+    }
+}
+
+fn write_todo(
+    out: &mut Vec<String>,
+    data: &AppData,
+    todo: &TodoItem,
+    level: usize,
+    visited: &mut HashSet<String>,
+) {
+    if !visited.insert(todo.id.clone()) {
+        return;
+    }
+
+    let keyword = if todo.completed { "DONE" } else { "TODO" };
+    let title = todo.title.lines().collect::<Vec<_>>().join(" ");
+    let cookie = match todo.priority {
+        Priority::Urgent => "[#A] ",
+        Priority::High => "[#B] ",
+        Priority::Medium => "[#C] ",
+        Priority::Low | Priority::None => "",
+    };
+    let mut headline = format!("{} {} {}{}", "*".repeat(level), keyword, cookie, title);
+    let mut tags: Vec<String> = todo.tags.iter().map(|t| org_tag(t)).collect();
+    if todo.archived {
+        tags.push("ARCHIVE".to_string());
+    }
+    if !tags.is_empty() {
+        headline.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    out.push(headline);
+
+    let mut nodes: Vec<&TimeNode> = todo.time_nodes.iter().collect();
+    nodes.sort_by_key(|tn| tn.date_time);
+    let deadline = nodes.iter().position(|tn| is_deadline(tn)).map(|i| nodes.remove(i));
+    let scheduled = if nodes.is_empty() { None } else { Some(nodes.remove(0)) };
+
+    // 显式的开始/截止日期优先，时间节点退回正文
+    let mut planning = Vec::new();
+    if let Some(completed_at) = todo.completed_at.filter(|_| todo.completed) {
+        planning.push(format!("CLOSED: {}", inactive_timestamp(&completed_at)));
+    }
+    match (todo.start_date, scheduled) {
+        (Some(start_date), node) => {
+            planning.push(format!("SCHEDULED: {}", active_timestamp(&start_date)));
+            nodes.extend(node);
+        }
+        (None, Some(node)) => planning.push(format!("SCHEDULED: {}", active_timestamp(&node.date_time))),
+        (None, None) => {}
+    }
+    match (todo.due_date, deadline) {
+        (Some(due_date), node) => {
+            planning.push(format!("DEADLINE: {}", active_timestamp(&due_date)));
+            nodes.extend(node);
+        }
+        (None, Some(node)) => planning.push(format!("DEADLINE: {}", active_timestamp(&node.date_time))),
+        (None, None) => {}
+    }
+    nodes.sort_by_key(|tn| tn.date_time);
+    if !planning.is_empty() {
+        out.push(planning.join(" "));
+    }
+
+    out.push(":PROPERTIES:".to_string());
+    out.push(format!(":ID:       {}", todo.id));
+    out.push(format!(":CREATED:  {}", inactive_timestamp(&todo.created_at)));
+    if let Some(archived_at) = &todo.archived_at {
+        out.push(format!(":ARCHIVED: {}", inactive_timestamp(archived_at)));
+    }
+    out.push(format!(":COLOR:    {:?}", todo.color_tag));
+    let fields = data.groups.iter()
+        .find(|g| g.id == todo.group_id)
+        .map(|g| g.custom_fields.as_slice())
+        .unwrap_or_default();
+    for field in fields {
+        if let Some(value) = todo.custom_fields.get(&field.id) {
+            out.push(format!(":{}: {}", property_name(&field.name), custom_field_value(field, value)));
+        }
+    }
+    out.push(":END:".to_string());
+
+    if let Some(details) = &todo.details {
+        out.extend(details.lines().map(body_line));
+    }
+    // 放不进 SCHEDULED/DEADLINE 的其余时间节点作为正文中的时间戳
+    for node in nodes {
+        let description = node.description.as_deref().unwrap_or("");
+        out.push(format!("{} {}", active_timestamp(&node.date_time), description).trim_end().to_string());
+    }
+
+    let mut children: Vec<&TodoItem> = data.todos.iter()
+        .filter(|t| t.parent_id.as_deref() == Some(todo.id.as_str()))
+        .collect();
+    children.sort_by(|a, b| a.order.total_cmp(&b.order));
+    for child in children {
+        write_todo(out, data, child, level + 1, visited);
+    }
+}
+
+/// 导出为 Emacs Org 文本：顶层分组为一级标题，子分组和待办逐级嵌套。
+pub fn export(data: &AppData) -> String {
+    let ids: HashSet<&str> = data.todos.iter().map(|t| t.id.as_str()).collect();
+
+    let mut out = vec!["#+TITLE: XhinkingTodo".to_string(), "#+TODO: TODO | DONE".to_string(), String::new()];
+    let mut visited = HashSet::new();
+
+    for (depth, group) in data.group_tree() {
+        out.push(format!("{} {}", "*".repeat(depth + 1), group.name));
+        out.push(":PROPERTIES:".to_string());
+        out.push(format!(":ID:       {}", group.id));
+        out.push(":END:".to_string());
+
+        // 父待办不存在时作为顶层待办
+        let mut roots: Vec<&TodoItem> = data.todos.iter()
+            .filter(|t| t.group_id == group.id)
+            .filter(|t| t.parent_id.as_deref().map(|p| !ids.contains(p)).unwrap_or(true))
+            .collect();
+        roots.sort_by(|a, b| a.order.total_cmp(&b.order));
+        for todo in roots {
+            write_todo(&mut out, data, todo, depth + 2, &mut visited);
+        }
+    }
+
+    // 分组已不存在或 parent_id 成环的待办，集中放在最后
+    let mut rest: Vec<&TodoItem> = data.todos.iter().filter(|t| !visited.contains(&t.id)).collect();
+    if !rest.is_empty() {
+        rest.sort_by(|a, b| a.order.total_cmp(&b.order));
+        out.push("* Unsorted".to_string());
+        for todo in rest {
+            write_todo(&mut out, data, todo, 2, &mut visited);
+        }
+    }
+
+    let mut content = out.join("\n");
+    content.push('\n');
+    content
+}
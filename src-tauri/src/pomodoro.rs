@@ -0,0 +1,105 @@
+use crate::models::*;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PomodoroPhase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+/// 当前番茄钟阶段，通过 "pomodoro-changed" 事件推送给前端。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PomodoroStatus {
+    pub todo_id: Option<String>,
+    pub phase: PomodoroPhase,
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    // 本轮已完成的番茄数，达到间隔后进入长休息并重新计数
+    pub completed_in_cycle: u32,
+}
+
+/// 番茄钟状态机，由后台提醒线程驱动，窗口隐藏时也会继续计时。
+#[derive(Debug, Default)]
+pub struct Pomodoro {
+    current: Option<PomodoroStatus>,
+    completed_in_cycle: u32,
+}
+
+impl Pomodoro {
+    pub fn status(&self) -> Option<PomodoroStatus> {
+        self.current.clone()
+    }
+
+    fn begin(&mut self, todo_id: Option<String>, phase: PomodoroPhase, minutes: u32, now: DateTime<Utc>) -> PomodoroStatus {
+        let status = PomodoroStatus {
+            todo_id,
+            phase,
+            started_at: now,
+            ends_at: now + Duration::minutes(minutes.max(1) as i64),
+            completed_in_cycle: self.completed_in_cycle,
+        };
+        self.current = Some(status.clone());
+        status
+    }
+
+    pub fn start_work(&mut self, todo_id: Option<String>, settings: &PomodoroSettings, now: DateTime<Utc>) -> PomodoroStatus {
+        self.begin(todo_id, PomodoroPhase::Work, settings.work_minutes, now)
+    }
+
+    // 停止当前阶段，返回被停止的阶段
+    pub fn stop(&mut self) -> Option<PomodoroStatus> {
+        self.current.take()
+    }
+
+    // 当前阶段到时则结束并返回；专注结束后自动进入休息，休息结束后等待下一次开始
+    pub fn tick(&mut self, settings: &PomodoroSettings, now: DateTime<Utc>) -> Option<PomodoroStatus> {
+        if self.current.as_ref().map(|s| s.ends_at > now).unwrap_or(true) {
+            return None;
+        }
+        let finished = self.current.take()?;
+
+        if finished.phase == PomodoroPhase::Work {
+            self.completed_in_cycle += 1;
+            let (phase, minutes) = if self.completed_in_cycle >= settings.long_break_interval.max(1) {
+                self.completed_in_cycle = 0;
+                (PomodoroPhase::LongBreak, settings.long_break_minutes)
+            } else {
+                (PomodoroPhase::ShortBreak, settings.short_break_minutes)
+            };
+            self.begin(finished.todo_id.clone(), phase, minutes, now);
+        }
+
+        Some(finished)
+    }
+}
+
+/// 记录一次专注；完成的番茄同时计入关联待办。
+pub fn record_focus_session(data: &mut AppData, status: &PomodoroStatus, end: DateTime<Utc>, completed: bool) {
+    if status.phase != PomodoroPhase::Work {
+        return;
+    }
+
+    data.focus_sessions.push(FocusSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        todo_id: status.todo_id.clone(),
+        start: status.started_at,
+        end: end.min(status.ends_at),
+        completed,
+    });
+
+    if completed {
+        if let Some(todo) = data.todos.iter_mut().find(|t| Some(&t.id) == status.todo_id.as_ref()) {
+            todo.pomodoros += 1;
+            todo.updated_at = Utc::now();
+        }
+    }
+}
+
+pub fn emit_status(app: &AppHandle, status: Option<PomodoroStatus>) {
+    if let Err(e) = app.emit_all("pomodoro-changed", status) {
+        eprintln!("Failed to emit pomodoro-changed: {}", e);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::models::*;
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportOptions {
+    pub filter: TodoFilter,
+    pub title: Option<String>,
+}
+
+// 与前端 index.css 中各主题的变量保持一致
+struct Palette {
+    background: &'static str,
+    card: &'static str,
+    text: &'static str,
+    text_secondary: &'static str,
+    border: &'static str,
+    accent: &'static str,
+    dark: bool,
+}
+
+fn palette(theme: Theme) -> Palette {
+    let (background, card, text, text_secondary, border, accent, dark) = match theme {
+        Theme::White => ("#FFFFFF", "#FFFFFF", "#1A1A1A", "#666666", "#D1D5DB", "#3B82F6", false),
+        Theme::MilkWhite => ("#F8F6F1", "#FDFCFA", "#2D2D2D", "#666666", "#D4CFC0", "#D97706", false),
+        Theme::LightRed => ("#EDE8E8", "#FAF5F5", "#2D2D2D", "#666666", "#CDB8B8", "#B87070", false),
+        Theme::LightYellow => ("#EDEBE0", "#FAF8ED", "#2D2D2D", "#666666", "#CDC79A", "#B8AD60", false),
+        Theme::LightGreen => ("#E5EDE8", "#F3FAF5", "#2D2D2D", "#666666", "#B0CDB8", "#5FA070", false),
+        Theme::LightBlue => ("#E5EAED", "#F3F7FA", "#2D2D2D", "#666666", "#B0C0D0", "#5A85A8", false),
+        Theme::LightPurple => ("#ECE5ED", "#FAF3FA", "#2D2D2D", "#666666", "#C8B0D0", "#9870B8", false),
+        Theme::DarkRed => ("#4A4040", "#413838", "#E8E2E2", "#C5BDBD", "#675858", "#D08080", true),
+        Theme::DarkYellow => ("#4A4638", "#413D32", "#E8E5D8", "#C5C2B0", "#675D48", "#D0C060", true),
+        Theme::DarkGreen => ("#3E4A45", "#38443F", "#E0E8E3", "#BCC5C0", "#5A6760", "#70B870", true),
+        Theme::DarkBlue => ("#3E4550", "#383E48", "#E0E5EA", "#BCC1C8", "#5A6068", "#6090D0", true),
+        Theme::DarkPurple => ("#483E50", "#413848", "#E7E0EA", "#C3BCC8", "#655A68", "#A070D0", true),
+        Theme::DarkGray => ("#2C2C2C", "#282828", "#F0F0F0", "#C8C8C8", "#404040", "#A3A3A3", true),
+        Theme::Black => ("#1A1A1A", "#181818", "#F0F0F0", "#C8C8C8", "#303030", "#A3A3A3", true),
+    };
+    Palette { background, card, text, text_secondary, border, accent, dark }
+}
+
+fn color_tag_hex(color_tag: ColorTag) -> &'static str {
+    match color_tag {
+        ColorTag::Red1 => "#FF4444",
+        ColorTag::Orange2 => "#FF8844",
+        ColorTag::Yellow3 => "#FFCC44",
+        ColorTag::Green4 => "#44CC44",
+        ColorTag::Cyan5 => "#44CCCC",
+        ColorTag::Blue6 => "#4488FF",
+        ColorTag::Purple7 => "#8844FF",
+    }
+}
+
+struct Labels {
+    lang: &'static str,
+    title: &'static str,
+    generated_at: &'static str,
+    open: &'static str,
+    done: &'static str,
+    completed_this_week: &'static str,
+    archived: &'static str,
+    due: &'static str,
+    reminder: &'static str,
+    empty: &'static str,
+}
+
+fn labels(language: Language) -> Labels {
+    match language {
+        Language::SimplifiedChinese => Labels {
+            lang: "zh-CN",
+            title: "待办周报",
+            generated_at: "生成时间",
+            open: "未完成",
+            done: "已完成",
+            completed_this_week: "本周完成",
+            archived: "已归档",
+            due: "截止",
+            reminder: "提醒",
+            empty: "暂无待办",
+        },
+        Language::TraditionalChinese => Labels {
+            lang: "zh-TW",
+            title: "待辦週報",
+            generated_at: "產生時間",
+            open: "未完成",
+            done: "已完成",
+            completed_this_week: "本週完成",
+            archived: "已歸檔",
+            due: "截止",
+            reminder: "提醒",
+            empty: "暫無待辦",
+        },
+        Language::English => Labels {
+            lang: "en",
+            title: "Weekly Report",
+            generated_at: "Generated",
+            open: "Open",
+            done: "Done",
+            completed_this_week: "Completed this week",
+            archived: "Archived",
+            due: "Due",
+            reminder: "Reminder",
+            empty: "No todos",
+        },
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn format_date_time(date_time: &DateTime<Utc>) -> String {
+    date_time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+// 本周一 00:00（本地时间）
+fn start_of_week() -> DateTime<Utc> {
+    let today = Local::now().date_naive();
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    monday.and_hms_opt(0, 0, 0)
+        .and_then(|d| Local.from_local_datetime(&d).earliest())
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now)
+}
+
+fn write_todo(
+    out: &mut String,
+    todos: &[&TodoItem],
+    todo: &TodoItem,
+    labels: &Labels,
+    visited: &mut HashSet<String>,
+) {
+    if !visited.insert(todo.id.clone()) {
+        return;
+    }
+
+    let class = if todo.completed { "todo done" } else { "todo" };
+    out.push_str(&format!("<li class=\"{}\">", class));
+    out.push_str(&format!(
+        "<span class=\"tag\" style=\"background:{}\"></span><span class=\"check\">{}</span><span class=\"title\">{}</span>",
+        color_tag_hex(todo.color_tag),
+        if todo.completed { "&#9745;" } else { "&#9744;" },
+        escape(&todo.title),
+    ));
+    if let Some(due_date) = &todo.due_date {
+        out.push_str(&format!("<span class=\"badge\">{} {}</span>", labels.due, format_date_time(due_date)));
+    }
+    for tag in &todo.tags {
+        out.push_str(&format!("<span class=\"badge\">#{}</span>", escape(tag)));
+    }
+    if todo.archived {
+        out.push_str(&format!("<span class=\"badge\">{}</span>", labels.archived));
+    }
+    if let Some(details) = todo.details.as_deref().filter(|d| !d.trim().is_empty()) {
+        out.push_str(&format!("<div class=\"details\">{}</div>", escape(details).replace('\n', "<br>")));
+    }
+
+    let mut nodes: Vec<&TimeNode> = todo.time_nodes.iter().collect();
+    nodes.sort_by_key(|tn| tn.date_time);
+    if !nodes.is_empty() {
+        out.push_str("<ul class=\"nodes\">");
+        for node in nodes {
+            out.push_str(&format!("<li><time>{}</time>", format_date_time(&node.date_time)));
+            if let Some(description) = &node.description {
+                out.push_str(&format!(" {}", escape(description)));
+            }
+            if node.reminder_enabled {
+                out.push_str(&format!(" <span class=\"badge\">{} -{}m</span>", labels.reminder, node.reminder_minutes_before));
+            }
+            out.push_str("</li>");
+        }
+        out.push_str("</ul>");
+    }
+
+    let mut children: Vec<&TodoItem> = todos.iter()
+        .copied()
+        .filter(|t| t.parent_id.as_deref() == Some(todo.id.as_str()))
+        .collect();
+    children.sort_by(|a, b| a.order.total_cmp(&b.order));
+    if !children.is_empty() {
+        out.push_str("<ul class=\"todos\">");
+        for child in children {
+            write_todo(out, todos, child, labels, visited);
+        }
+        out.push_str("</ul>");
+    }
+
+    out.push_str("</li>");
+}
+
+fn stylesheet(palette: &Palette) -> String {
+    let print_override = if palette.dark {
+        "body { background: #FFFFFF; color: #1A1A1A; } section, .summary { background: #FFFFFF; } .meta, .details, .nodes { color: #555555; }"
+    } else {
+        ""
+    };
+    format!(
+        r#"
+* {{ box-sizing: border-box; }}
+body {{ margin: 0; padding: 24px; font-family: -apple-system, "Segoe UI", "PingFang SC", "Microsoft YaHei", sans-serif; font-size: 13px; line-height: 1.5; background: {background}; color: {text}; }}
+h1 {{ margin: 0 0 4px; font-size: 22px; color: {accent}; }}
+h2 {{ margin: 0 0 8px; font-size: 16px; border-bottom: 2px solid {accent}; padding-bottom: 4px; }}
+.meta {{ color: {text_secondary}; margin-bottom: 12px; }}
+.summary {{ display: flex; gap: 16px; padding: 8px 12px; border: 1px solid {border}; border-radius: 6px; background: {card}; margin-bottom: 16px; }}
+section {{ background: {card}; border: 1px solid {border}; border-radius: 6px; padding: 12px 16px; margin-bottom: 16px; page-break-inside: avoid; break-inside: avoid; }}
+ul {{ list-style: none; margin: 0; padding: 0; }}
+ul.todos ul.todos {{ margin-left: 20px; }}
+li.todo {{ padding: 3px 0; }}
+li.todo.done > .title {{ text-decoration: line-through; color: {text_secondary}; }}
+.tag {{ display: inline-block; width: 8px; height: 8px; border-radius: 50%; margin-right: 6px; vertical-align: middle; -webkit-print-color-adjust: exact; print-color-adjust: exact; }}
+.check {{ margin-right: 4px; }}
+.badge {{ margin-left: 6px; padding: 0 6px; border: 1px solid {border}; border-radius: 8px; font-size: 11px; color: {text_secondary}; }}
+.details {{ margin: 2px 0 0 26px; color: {text_secondary}; white-space: normal; }}
+.nodes {{ margin: 2px 0 0 26px; color: {text_secondary}; font-size: 12px; }}
+.empty {{ color: {text_secondary}; }}
+@page {{ size: A4; margin: 15mm; }}
+@media print {{ body {{ padding: 0; }} {print_override} }}
+"#,
+        background = palette.background,
+        card = palette.card,
+        text = palette.text,
+        text_secondary = palette.text_secondary,
+        border = palette.border,
+        accent = palette.accent,
+        print_override = print_override,
+    )
+}
+
+/// 生成可打印的 HTML 报告，使用当前主题和语言。
+pub fn render(data: &AppData, options: &ReportOptions) -> String {
+    let labels = labels(data.settings.language);
+    let palette = palette(data.settings.theme);
+    let title = options.title.clone().unwrap_or_else(|| labels.title.to_string());
+
+    let filter = data.expand_group_filter(&options.filter);
+    let todos: Vec<&TodoItem> = data.todos.iter().filter(|t| filter.matches(t)).collect();
+    let ids: HashSet<&str> = todos.iter().map(|t| t.id.as_str()).collect();
+
+    let week_start = start_of_week();
+    let mut completed_this_week: Vec<&TodoItem> = todos.iter()
+        .copied()
+        .filter(|t| t.completed && t.completed_at.map(|c| c >= week_start).unwrap_or(false))
+        .collect();
+    completed_this_week.sort_by_key(|t| t.completed_at);
+
+    let open_count = todos.iter().filter(|t| !t.completed).count();
+    let done_count = todos.len() - open_count;
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        labels.lang,
+        escape(&title),
+        stylesheet(&palette),
+    ));
+    out.push_str(&format!("<h1>{}</h1>\n", escape(&title)));
+    out.push_str(&format!("<div class=\"meta\">{}: {}</div>\n", labels.generated_at, format_date_time(&Utc::now())));
+    out.push_str(&format!(
+        "<div class=\"summary\"><span>{}: {}</span><span>{}: {}</span><span>{}: {}</span></div>\n",
+        labels.open, open_count, labels.done, done_count, labels.completed_this_week, completed_this_week.len(),
+    ));
+
+    out.push_str(&format!("<section>\n<h2>{}</h2>\n", labels.completed_this_week));
+    if completed_this_week.is_empty() {
+        out.push_str(&format!("<p class=\"empty\">{}</p>\n", labels.empty));
+    } else {
+        out.push_str("<ul class=\"todos\">");
+        for todo in &completed_this_week {
+            out.push_str(&format!(
+                "<li class=\"todo\"><span class=\"tag\" style=\"background:{}\"></span><span class=\"title\">{}</span> <span class=\"badge\">{}</span></li>",
+                color_tag_hex(todo.color_tag),
+                escape(&todo.title),
+                format_date_time(&todo.completed_at.unwrap_or(todo.updated_at)),
+            ));
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</section>\n");
+
+    // 子分组紧跟在上级之后，标题显示完整路径
+    let groups: Vec<&TodoGroup> = data.group_tree().into_iter()
+        .map(|(_, g)| g)
+        .filter(|g| filter.group_ids.as_ref().map(|ids| ids.contains(&g.id)).unwrap_or(true))
+        .collect();
+
+    let mut visited = HashSet::new();
+    for group in groups {
+        let mut roots: Vec<&TodoItem> = todos.iter()
+            .copied()
+            .filter(|t| t.group_id == group.id)
+            .filter(|t| t.parent_id.as_deref().map(|p| !ids.contains(p)).unwrap_or(true))
+            .collect();
+        roots.sort_by(|a, b| a.order.total_cmp(&b.order));
+
+        let heading = match &group.icon {
+            Some(icon) => format!("{} {}", icon, data.group_path(&group.id)),
+            None => data.group_path(&group.id),
+        };
+        out.push_str(&format!("<section>\n<h2>{}</h2>\n", escape(&heading)));
+        if let Some(description) = &group.description {
+            out.push_str(&format!("<p class=\"meta\">{}</p>\n", escape(description)));
+        }
+        if roots.is_empty() {
+            out.push_str(&format!("<p class=\"empty\">{}</p>\n", labels.empty));
+        } else {
+            out.push_str("<ul class=\"todos\">");
+            for todo in roots {
+                write_todo(&mut out, &todos, todo, &labels, &mut visited);
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -0,0 +1,170 @@
+use crate::models::*;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum StatsBucket {
+    Day,
+    Week,
+}
+
+/// 统计范围；未指定 from 时从最早的记录开始，未指定 to 时到今天为止。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub bucket: StatsBucket,
+}
+
+impl Default for StatsRange {
+    fn default() -> Self {
+        StatsRange {
+            from: None,
+            to: None,
+            bucket: StatsBucket::Day,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeriodCount {
+    // 本地日期（YYYY-MM-DD），按周统计时为该周周一
+    pub period: String,
+    pub created: usize,
+    pub completed: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BreakdownCount {
+    pub key: String,
+    pub created: usize,
+    pub completed: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub periods: Vec<PeriodCount>,
+    pub created: usize,
+    pub completed: usize,
+    // 范围内完成的待办从创建到完成的平均小时数
+    pub average_lead_time_hours: Option<f64>,
+    pub by_group: Vec<BreakdownCount>,
+    pub by_color: Vec<BreakdownCount>,
+    // 截至今天（今天尚未完成时截至昨天）连续有完成记录的天数，不受范围限制
+    pub current_streak_days: u32,
+}
+
+fn local_date(date_time: &DateTime<Utc>) -> NaiveDate {
+    date_time.with_timezone(&Local).date_naive()
+}
+
+fn bucket_start(date: NaiveDate, bucket: StatsBucket) -> NaiveDate {
+    match bucket {
+        StatsBucket::Day => date,
+        StatsBucket::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+    }
+}
+
+fn breakdown<'a>(counts: &'a mut Vec<BreakdownCount>, key: &str) -> &'a mut BreakdownCount {
+    match counts.iter().position(|c| c.key == key) {
+        Some(index) => &mut counts[index],
+        None => {
+            counts.push(BreakdownCount { key: key.to_string(), ..Default::default() });
+            counts.last_mut().unwrap()
+        }
+    }
+}
+
+fn current_streak(data: &AppData) -> u32 {
+    let days: HashSet<NaiveDate> = data.todos.iter()
+        .filter_map(|t| t.completed_at.as_ref())
+        .map(local_date)
+        .collect();
+
+    let today = Local::now().date_naive();
+    let mut day = if days.contains(&today) { today } else { today - Duration::days(1) };
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+/// 统计范围内的新建与完成情况。
+pub fn compute(data: &AppData, range: &StatsRange) -> Stats {
+    let in_range = |date: &DateTime<Utc>| {
+        range.from.map(|from| *date >= from).unwrap_or(true)
+            && range.to.map(|to| *date <= to).unwrap_or(true)
+    };
+
+    let created: Vec<&TodoItem> = data.todos.iter().filter(|t| in_range(&t.created_at)).collect();
+    let completed: Vec<&TodoItem> = data.todos.iter()
+        .filter(|t| t.completed && t.completed_at.as_ref().map(in_range).unwrap_or(false))
+        .collect();
+
+    let mut stats = Stats {
+        created: created.len(),
+        completed: completed.len(),
+        current_streak_days: current_streak(data),
+        ..Default::default()
+    };
+
+    let lead_times: Vec<i64> = completed.iter()
+        .filter_map(|t| t.completed_at.map(|c| (c - t.created_at).num_seconds().max(0)))
+        .collect();
+    if !lead_times.is_empty() {
+        let average_seconds = lead_times.iter().sum::<i64>() as f64 / lead_times.len() as f64;
+        stats.average_lead_time_hours = Some(average_seconds / 3600.0);
+    }
+
+    for todo in &created {
+        breakdown(&mut stats.by_group, &todo.group_id).created += 1;
+        breakdown(&mut stats.by_color, &format!("{:?}", todo.color_tag)).created += 1;
+    }
+    for todo in &completed {
+        breakdown(&mut stats.by_group, &todo.group_id).completed += 1;
+        breakdown(&mut stats.by_color, &format!("{:?}", todo.color_tag)).completed += 1;
+    }
+    stats.by_group.sort_by(|a, b| b.completed.cmp(&a.completed).then_with(|| b.created.cmp(&a.created)));
+    stats.by_color.sort_by(|a, b| a.key.cmp(&b.key));
+
+    // 连续的时间段，没有记录的时段计为 0
+    let created_dates = created.iter().map(|t| local_date(&t.created_at));
+    let completed_dates = completed.iter().filter_map(|t| t.completed_at.as_ref()).map(local_date);
+    let first = range.from.as_ref().map(local_date)
+        .or_else(|| created_dates.clone().chain(completed_dates.clone()).min());
+    let last = range.to.as_ref().map(local_date).unwrap_or_else(|| Local::now().date_naive());
+
+    if let Some(first) = first {
+        let step = match range.bucket {
+            StatsBucket::Day => Duration::days(1),
+            StatsBucket::Week => Duration::weeks(1),
+        };
+        let mut period = bucket_start(first, range.bucket);
+        while period <= last {
+            stats.periods.push(PeriodCount {
+                period: period.format("%Y-%m-%d").to_string(),
+                ..Default::default()
+            });
+            period += step;
+        }
+
+        let index = |date: NaiveDate| {
+            let key = bucket_start(date, range.bucket).format("%Y-%m-%d").to_string();
+            stats.periods.iter().position(|p| p.period == key)
+        };
+        let created_indexes: Vec<usize> = created_dates.filter_map(index).collect();
+        let completed_indexes: Vec<usize> = completed_dates.filter_map(index).collect();
+        for i in created_indexes {
+            stats.periods[i].created += 1;
+        }
+        for i in completed_indexes {
+            stats.periods[i].completed += 1;
+        }
+    }
+
+    stats
+}
@@ -1,10 +1,13 @@
-use crate::models::AppData;
+use crate::models::{AppData, ExportedData};
+use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use tauri::api::path::app_data_dir;
 
 pub struct Storage {
     pub data_path: PathBuf,
+    // 复制进来的附件，按待办 id 分目录存放
+    pub attachments_dir: PathBuf,
 }
 
 impl Storage {
@@ -17,8 +20,9 @@ impl Storage {
             .map_err(|e| format!("Failed to create data directory: {}", e))?;
         
         let data_path = app_data_dir.join("data.json");
+        let attachments_dir = app_data_dir.join("attachments");
         
-        Ok(Storage { data_path })
+        Ok(Storage { data_path, attachments_dir })
     }
 
     pub fn load(&self) -> Result<AppData, String> {
@@ -29,8 +33,9 @@ impl Storage {
         let content = fs::read_to_string(&self.data_path)
             .map_err(|e| format!("Failed to read data file: {}", e))?;
         
-        let data: AppData = serde_json::from_str(&content)
+        let mut data: AppData = serde_json::from_str(&content)
             .map_err(|e| format!("Failed to parse data file: {}", e))?;
+        data.migrate();
         
         Ok(data)
     }
@@ -45,7 +50,7 @@ impl Storage {
         Ok(())
     }
 
-    pub fn export_to_file(&self, data: &AppData, path: &str) -> Result<(), String> {
+    pub fn export_to_file<T: Serialize>(&self, data: &T, path: &str) -> Result<(), String> {
         let content = serde_json::to_string_pretty(data)
             .map_err(|e| format!("Failed to serialize data: {}", e))?;
         
@@ -55,13 +60,87 @@ impl Storage {
         Ok(())
     }
 
-    pub fn import_from_file(&self, path: &str) -> Result<AppData, String> {
+    pub fn import_from_file(&self, path: &str) -> Result<ExportedData, String> {
         let content = fs::read_to_string(path)
             .map_err(|e| format!("Failed to read import file: {}", e))?;
         
-        let data: AppData = serde_json::from_str(&content)
+        let mut data: ExportedData = serde_json::from_str(&content)
             .map_err(|e| format!("Failed to parse import file: {}", e))?;
+        data.migrate();
         
         Ok(data)
     }
+
+    pub fn export_text_to_file(&self, content: &str, path: &str) -> Result<(), String> {
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to export data: {}", e))?;
+        
+        Ok(())
+    }
+
+    pub fn import_text_from_file(&self, path: &str) -> Result<String, String> {
+        fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read import file: {}", e))
+    }
+
+    // 复制附件到 attachments/<todo_id>/，重名时加前缀；返回复制后的路径
+    pub fn copy_attachment(&self, source: &str, todo_id: &str) -> Result<PathBuf, String> {
+        let source = Path::new(source);
+        let file_name = source.file_name()
+            .ok_or_else(|| "Invalid attachment path".to_string())?
+            .to_string_lossy()
+            .to_string();
+        
+        let dir = self.todo_attachments_dir(todo_id)?;
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create attachment directory: {}", e))?;
+        
+        let mut target = dir.join(&file_name);
+        if target.exists() {
+            let prefix = uuid::Uuid::new_v4().to_string();
+            target = dir.join(format!("{}-{}", &prefix[..8], file_name));
+        }
+        
+        fs::copy(source, &target)
+            .map_err(|e| format!("Failed to copy attachment: {}", e))?;
+        
+        Ok(target)
+    }
+
+    // 待办的附件目录；id 必须是单个普通路径段，避免 ".." 等指向附件目录之外
+    fn todo_attachments_dir(&self, todo_id: &str) -> Result<PathBuf, String> {
+        let mut components = Path::new(todo_id).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => Ok(self.attachments_dir.join(todo_id)),
+            _ => Err(format!("Invalid todo id: {}", todo_id)),
+        }
+    }
+
+    // 只删除附件目录内的文件，链接的外部文件不受影响；比较前解析 ".." 和符号链接
+    pub fn remove_attachment_file(&self, path: &str) -> Result<(), String> {
+        let (path, root) = match (Path::new(path).canonicalize(), self.attachments_dir.canonicalize()) {
+            (Ok(path), Ok(root)) => (path, root),
+            _ => return Ok(()),
+        };
+        if !path.starts_with(&root) || !path.is_file() {
+            return Ok(());
+        }
+        
+        fs::remove_file(path)
+            .map_err(|e| format!("Failed to delete attachment: {}", e))?;
+        
+        Ok(())
+    }
+
+    pub fn remove_todo_attachments(&self, todo_id: &str) -> Result<(), String> {
+        let dir = self.todo_attachments_dir(todo_id)?;
+        if !dir.exists() {
+            return Ok(());
+        }
+        
+        fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to delete attachments: {}", e))?;
+        
+        Ok(())
+    }
 }
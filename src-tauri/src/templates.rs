@@ -0,0 +1,263 @@
+use crate::models::*;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+/// 按相对偏移计算时间。偏移由空格分隔的若干部分组成：
+/// "+2d"、"-1w"、"+3h"、"+30m" 以及表示当天时刻的 "09:00"，如 "+2d 09:00"。
+pub fn apply_offset(base: DateTime<Utc>, offset: &str) -> Result<DateTime<Utc>, String> {
+    let invalid = || format!("Invalid offset: {}", offset);
+    let mut days = 0i64;
+    let mut minutes = 0i64;
+    let mut time: Option<NaiveTime> = None;
+
+    for token in offset.split_whitespace() {
+        if let Ok(t) = NaiveTime::parse_from_str(token, "%H:%M") {
+            time = Some(t);
+            continue;
+        }
+        let unit = token.chars().last().ok_or_else(invalid)?;
+        let value: i64 = token[..token.len() - unit.len_utf8()].parse().map_err(|_| invalid())?;
+        // 偏移来自模板或用户输入，过大时返回错误而不是溢出
+        match unit {
+            'd' => days = days.checked_add(value).ok_or_else(invalid)?,
+            'w' => days = value.checked_mul(7).and_then(|v| days.checked_add(v)).ok_or_else(invalid)?,
+            'h' => minutes = value.checked_mul(60).and_then(|v| minutes.checked_add(v)).ok_or_else(invalid)?,
+            'm' => minutes = minutes.checked_add(value).ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        }
+    }
+
+    // 天数和时刻按本地时间计算，避免跨夏令时后时刻偏移
+    let local = base.with_timezone(&Local);
+    let date = Duration::try_days(days)
+        .and_then(|d| local.date_naive().checked_add_signed(d))
+        .ok_or_else(invalid)?;
+    let naive = date.and_time(time.unwrap_or_else(|| local.time()));
+    let date_time = Local.from_local_datetime(&naive)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .ok_or_else(invalid)?;
+
+    Duration::try_minutes(minutes)
+        .and_then(|m| date_time.checked_add_signed(m))
+        .ok_or_else(invalid)
+}
+
+fn format_offset(base_day: NaiveDate, date_time: &DateTime<Utc>) -> String {
+    let local = date_time.with_timezone(&Local);
+    let days = (local.date_naive() - base_day).num_days();
+    format!("{:+}d {}", days, local.format("%H:%M"))
+}
+
+fn todo_dates(todo: &TodoItem) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+    todo.start_date.into_iter()
+        .chain(todo.due_date)
+        .chain(todo.time_nodes.iter().map(|tn| tn.date_time))
+}
+
+fn template_item(data: &AppData, todo: &TodoItem, base_day: NaiveDate, visited: &mut Vec<String>) -> TemplateItem {
+    visited.push(todo.id.clone());
+
+    let mut children: Vec<&TodoItem> = data.todos.iter()
+        .filter(|t| t.parent_id.as_deref() == Some(todo.id.as_str()))
+        .filter(|t| !visited.contains(&t.id))
+        .collect();
+    children.sort_by(|a, b| a.order.total_cmp(&b.order));
+
+    TemplateItem {
+        title: todo.title.clone(),
+        details: todo.details.clone(),
+        color_tag: todo.color_tag,
+        priority: todo.priority,
+        tags: todo.tags.clone(),
+        estimate_minutes: todo.estimate_minutes,
+        start_offset: todo.start_date.as_ref().map(|d| format_offset(base_day, d)),
+        due_offset: todo.due_date.as_ref().map(|d| format_offset(base_day, d)),
+        time_nodes: todo.time_nodes.iter()
+            .map(|tn| TemplateTimeNode {
+                offset: format_offset(base_day, &tn.date_time),
+                description: tn.description.clone(),
+                reminder_enabled: tn.reminder_enabled,
+                reminder_minutes_before: tn.reminder_minutes_before,
+            })
+            .collect(),
+        custom_fields: todo.custom_fields.clone(),
+        children: children.into_iter()
+            .map(|child| template_item(data, child, base_day, visited))
+            .collect(),
+    }
+}
+
+/// 由待办及其子待办生成模板，时间以子树中最早的日期为基准。
+pub fn from_subtree(data: &AppData, todo_id: &str, name: String) -> Result<TodoTemplate, String> {
+    let root = data.todos.iter()
+        .find(|t| t.id == todo_id)
+        .ok_or_else(|| "Todo not found".to_string())?;
+
+    let base_day = data.subtree(todo_id).into_iter()
+        .flat_map(todo_dates)
+        .min()
+        .map(|d| d.with_timezone(&Local).date_naive())
+        .unwrap_or_else(|| Local::now().date_naive());
+
+    let now = Utc::now();
+    Ok(TodoTemplate {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        items: vec![template_item(data, root, base_day, &mut Vec::new())],
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+/// 检查模板中的所有偏移是否可以解析。
+pub fn validate(template: &TodoTemplate) -> Result<(), String> {
+    fn check(item: &TemplateItem) -> Result<(), String> {
+        let now = Utc::now();
+        for offset in item.start_offset.iter().chain(&item.due_offset).chain(item.time_nodes.iter().map(|tn| &tn.offset)) {
+            apply_offset(now, offset)?;
+        }
+        item.children.iter().try_for_each(check)
+    }
+
+    if template.name.trim().is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    template.items.iter().try_for_each(check)
+}
+
+fn build_todos(
+    item: &TemplateItem,
+    group: &TodoGroup,
+    parent_id: Option<String>,
+    order: f64,
+    base: DateTime<Utc>,
+    todos: &mut Vec<TodoItem>,
+) -> Result<(), String> {
+    let mut todo = TodoItem::new(item.title.clone(), group.id.clone(), order);
+    todo.parent_id = parent_id;
+    todo.details = item.details.clone();
+    todo.color_tag = item.color_tag;
+    todo.priority = item.priority;
+    todo.tags = normalize_tags(item.tags.clone());
+    todo.estimate_minutes = item.estimate_minutes;
+    todo.custom_fields = group.retain_custom_fields(&item.custom_fields);
+    todo.start_date = item.start_offset.as_deref().map(|o| apply_offset(base, o)).transpose()?;
+    todo.due_date = item.due_offset.as_deref().map(|o| apply_offset(base, o)).transpose()?;
+    for node in &item.time_nodes {
+        let mut time_node = TimeNode::new(apply_offset(base, &node.offset)?, node.description.clone());
+        time_node.reminder_enabled = node.reminder_enabled;
+        time_node.reminder_minutes_before = node.reminder_minutes_before;
+        todo.time_nodes.push(time_node);
+    }
+
+    let id = todo.id.clone();
+    todos.push(todo);
+    for (index, child) in item.children.iter().enumerate() {
+        build_todos(child, group, Some(id.clone()), index as f64, base, todos)?;
+    }
+    Ok(())
+}
+
+/// 在分组末尾按模板创建待办，id 全部重新生成；返回新建的待办。
+pub fn instantiate(
+    data: &mut AppData,
+    template: &TodoTemplate,
+    group_id: &str,
+    base: DateTime<Utc>,
+) -> Result<Vec<TodoItem>, String> {
+    let group = data.groups.iter()
+        .find(|g| g.id == group_id)
+        .cloned()
+        .ok_or_else(|| "Group not found".to_string())?;
+    let next_order = data.next_todo_order(group_id, None);
+
+    // 先全部生成，偏移有误时不留下半棵树
+    let mut todos = Vec::new();
+    for (index, item) in template.items.iter().enumerate() {
+        build_todos(item, &group, None, next_order + index as f64, base, &mut todos)?;
+    }
+
+    data.todos.extend(todos.iter().cloned());
+    Ok(todos)
+}
+
+/// 复制待办（可含子待办）但不写入数据：重新生成 id，重置完成和归档状态，
+/// 按 date_offset 平移所有日期。第一个是原待办的副本，子孙副本在后；
+/// 复制的附件文件由调用方处理。
+pub fn duplicate_copies(data: &AppData, todo_id: &str, options: &DuplicateOptions) -> Result<Vec<TodoItem>, String> {
+    let original = data.todos.iter()
+        .find(|t| t.id == todo_id)
+        .cloned()
+        .ok_or_else(|| "Todo not found".to_string())?;
+
+    // 原待办在最前，子孙按原有顺序排在后面
+    let mut sources: Vec<TodoItem> = if options.include_children {
+        data.subtree(todo_id).into_iter().cloned().collect()
+    } else {
+        vec![original.clone()]
+    };
+    sources[1..].sort_by(|a, b| a.order.total_cmp(&b.order));
+
+    let shift = |date_time: DateTime<Utc>| -> Result<DateTime<Utc>, String> {
+        match &options.date_offset {
+            Some(offset) => apply_offset(date_time, offset),
+            None => Ok(date_time),
+        }
+    };
+
+    let new_ids: Vec<(String, String)> = sources.iter()
+        .map(|t| (t.id.clone(), uuid::Uuid::new_v4().to_string()))
+        .collect();
+    let map_id = |id: &str| new_ids.iter().find(|(old, _)| old == id).map(|(_, new)| new.clone());
+
+    let now = Utc::now();
+    let mut copies = Vec::new();
+    for (index, source) in sources.iter().enumerate() {
+        let mut copy = source.clone();
+        copy.id = new_ids[index].1.clone();
+        copy.parent_id = if index == 0 {
+            original.parent_id.clone()
+        } else {
+            source.parent_id.as_deref().and_then(map_id)
+        };
+        copy.blocked_by = source.blocked_by.iter()
+            .map(|b| map_id(b).unwrap_or_else(|| b.clone()))
+            .collect();
+        copy.completed = false;
+        copy.completed_at = None;
+        copy.archived = false;
+        copy.archived_at = None;
+        copy.pomodoros = 0;
+        copy.history = Vec::new();
+        copy.start_date = copy.start_date.map(shift).transpose()?;
+        copy.due_date = copy.due_date.map(shift).transpose()?;
+        for node in copy.time_nodes.iter_mut() {
+            node.id = uuid::Uuid::new_v4().to_string();
+            node.date_time = shift(node.date_time)?;
+            node.created_at = now;
+        }
+        for attachment in copy.attachments.iter_mut() {
+            attachment.id = uuid::Uuid::new_v4().to_string();
+            attachment.created_at = now;
+        }
+        copy.created_at = now;
+        copy.updated_at = now;
+        copies.push(copy);
+    }
+
+    Ok(copies)
+}
+
+/// 写入 duplicate_copies 生成的副本，返回放置后的副本
+pub fn insert_duplicates(data: &mut AppData, todo_id: &str, copies: Vec<TodoItem>) -> Result<Vec<TodoItem>, String> {
+    let new_ids: Vec<String> = copies.iter().map(|t| t.id.clone()).collect();
+    let copy_id = new_ids.first().cloned().ok_or_else(|| "Nothing to duplicate".to_string())?;
+
+    // 子孙副本沿用原顺序，只需把副本本身放到原待办之后
+    data.todos.extend(copies);
+    data.place_todo_after(&copy_id, Some(todo_id))?;
+
+    Ok(new_ids.iter()
+        .filter_map(|new| data.todos.iter().find(|t| &t.id == new).cloned())
+        .collect())
+}
@@ -0,0 +1,262 @@
+use crate::csv::{parse_date_time, parse_records};
+use crate::models::*;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Todoist 的 p1 为最高优先级
+fn color_tag_from_priority(priority: u8) -> ColorTag {
+    match priority {
+        1 => ColorTag::Red1,
+        2 => ColorTag::Orange2,
+        3 => ColorTag::Yellow3,
+        _ => ColorTag::default(),
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TodoistBackup {
+    projects: Vec<TodoistProject>,
+    #[serde(alias = "tasks")]
+    items: Vec<TodoistItem>,
+    reminders: Vec<TodoistReminder>,
+    sections: Vec<serde_json::Value>,
+    notes: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TodoistProject {
+    id: String,
+    name: String,
+    parent_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TodoistItem {
+    id: String,
+    project_id: String,
+    content: String,
+    description: String,
+    // JSON 中 4 为最高优先级，与 CSV 相反
+    priority: u8,
+    parent_id: Option<String>,
+    child_order: i32,
+    #[serde(alias = "is_completed")]
+    checked: bool,
+    is_deleted: bool,
+    due: Option<TodoistDue>,
+    labels: Vec<String>,
+    #[serde(alias = "created_at")]
+    added_at: Option<String>,
+    completed_at: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TodoistDue {
+    date: String,
+    datetime: Option<String>,
+    string: String,
+    is_recurring: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TodoistReminder {
+    item_id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    due: Option<TodoistDue>,
+    minute_offset: Option<i32>,
+    is_deleted: bool,
+}
+
+fn parse_due(due: &TodoistDue) -> Option<DateTime<Utc>> {
+    due.datetime.as_deref()
+        .and_then(parse_date_time)
+        .or_else(|| parse_date_time(&due.date))
+}
+
+/// 导入 Todoist JSON 备份（Sync API 或 REST API 格式）。
+pub fn import_json(content: &str, data: &mut AppData) -> Result<ImportSummary, String> {
+    let backup: TodoistBackup = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse Todoist file: {}", e))?;
+
+    let mut summary = ImportSummary::default();
+    let default_group_id = data.default_group_id();
+
+    let mut group_ids: HashMap<String, String> = HashMap::new();
+    for project in &backup.projects {
+        let (group_id, created) = data.find_or_create_group(&project.name);
+        if created {
+            summary.groups_created += 1;
+        }
+        if project.parent_id.is_some() {
+            summary.unmapped.push(format!("Sub-project \"{}\" imported as a top-level group", project.name));
+        }
+        group_ids.insert(project.id.clone(), group_id);
+    }
+    if !backup.sections.is_empty() {
+        summary.unmapped.push(format!("{} section(s) ignored; tasks kept in their project", backup.sections.len()));
+    }
+    if !backup.notes.is_empty() {
+        summary.unmapped.push(format!("{} comment(s) ignored", backup.notes.len()));
+    }
+
+    let mut items: Vec<&TodoistItem> = backup.items.iter().filter(|i| !i.is_deleted).collect();
+    items.sort_by_key(|i| i.child_order);
+
+    let mut todo_ids: HashMap<String, String> = HashMap::new();
+    let mut todos = Vec::new();
+
+    for item in items {
+        let group_id = group_ids.get(&item.project_id).cloned().unwrap_or_else(|| default_group_id.clone());
+        let mut todo = TodoItem::new(item.content.clone(), group_id, 0.0);
+        if !item.description.is_empty() {
+            todo.details = Some(item.description.clone());
+        }
+        todo.color_tag = color_tag_from_priority(5u8.saturating_sub(item.priority));
+        todo.priority = Priority::from(todo.color_tag);
+        todo.completed = item.checked;
+        if let Some(added_at) = item.added_at.as_deref().and_then(parse_date_time) {
+            todo.created_at = added_at;
+            todo.updated_at = added_at;
+        }
+        if todo.completed {
+            todo.completed_at = item.completed_at.as_deref()
+                .and_then(parse_date_time)
+                .or(Some(todo.updated_at));
+        }
+
+        if let Some(due) = &item.due {
+            match parse_due(due) {
+                Some(date_time) => {
+                    todo.due_date = Some(date_time);
+                    todo.time_nodes.push(TimeNode::new(date_time, Some("due".to_string())));
+                }
+                None => summary.unmapped.push(format!("\"{}\": due date \"{}\" not recognised", item.content, due.string)),
+            }
+            if due.is_recurring {
+                summary.unmapped.push(format!("\"{}\": recurring due \"{}\" imported as a single date", item.content, due.string));
+            }
+        }
+        todo.tags = normalize_tags(item.labels.clone());
+
+        todo_ids.insert(item.id.clone(), todo.id.clone());
+        todos.push((item, todo));
+    }
+
+    for reminder in backup.reminders.iter().filter(|r| !r.is_deleted) {
+        let Some((item, todo)) = todos.iter_mut().find(|(i, _)| i.id == reminder.item_id) else {
+            continue;
+        };
+        let absolute = reminder.due.as_ref().and_then(parse_due);
+        match (reminder.kind.as_str(), reminder.minute_offset, absolute) {
+            ("relative", Some(offset), _) if !todo.time_nodes.is_empty() => {
+                todo.time_nodes[0].reminder_enabled = true;
+                todo.time_nodes[0].reminder_minutes_before = offset;
+            }
+            ("absolute", _, Some(date_time)) => {
+                let mut node = TimeNode::new(date_time, Some("reminder".to_string()));
+                node.reminder_enabled = true;
+                todo.time_nodes.push(node);
+            }
+            (kind, _, _) => summary.unmapped.push(format!("\"{}\": {} reminder ignored", item.content, kind)),
+        }
+    }
+
+    for (item, mut todo) in todos {
+        todo.parent_id = item.parent_id.as_ref().and_then(|p| todo_ids.get(p).cloned());
+        // 按 child_order 依次追加到各自父待办的末尾
+        todo.order = data.next_todo_order(&todo.group_id, todo.parent_id.as_deref());
+        data.todos.push(todo);
+        summary.todos_imported += 1;
+    }
+
+    Ok(summary)
+}
+
+/// 导入 Todoist 导出的项目 CSV，文件名即项目名。
+pub fn import_csv(content: &str, project_name: &str, data: &mut AppData) -> Result<ImportSummary, String> {
+    let mut records = parse_records(content).into_iter();
+    let header = records.next().ok_or_else(|| "CSV file is empty".to_string())?;
+    let column = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+
+    let type_column = column("TYPE").ok_or_else(|| "Not a Todoist CSV export".to_string())?;
+    let content_column = column("CONTENT").ok_or_else(|| "Not a Todoist CSV export".to_string())?;
+    let description_column = column("DESCRIPTION");
+    let priority_column = column("PRIORITY");
+    let indent_column = column("INDENT");
+    let date_column = column("DATE");
+
+    let mut summary = ImportSummary::default();
+    let (group_id, created) = data.find_or_create_group(project_name);
+    if created {
+        summary.groups_created += 1;
+    }
+
+    // 按缩进层级记录最近的父待办
+    let mut parents: Vec<String> = Vec::new();
+
+    for record in records {
+        let cell = |index: Option<usize>| -> &str {
+            index.and_then(|i| record.get(i)).map(|v| v.trim()).unwrap_or("")
+        };
+
+        let title = cell(Some(content_column));
+        match cell(Some(type_column)) {
+            "task" => {}
+            "note" => {
+                // 备注追加到上一个待办的详情中
+                if let Some(todo) = data.todos.last_mut().filter(|_| summary.todos_imported > 0) {
+                    let details = todo.details.get_or_insert_with(String::new);
+                    if !details.is_empty() {
+                        details.push('\n');
+                    }
+                    details.push_str(title);
+                }
+                continue;
+            }
+            "section" => {
+                summary.unmapped.push(format!("Section \"{}\" ignored; tasks kept in \"{}\"", title, project_name));
+                continue;
+            }
+            _ => continue,
+        }
+
+        let indent = cell(indent_column).parse::<usize>().unwrap_or(1).max(1);
+        parents.truncate(indent - 1);
+
+        let order = data.next_todo_order(&group_id, parents.last().map(|p| p.as_str()));
+        let mut todo = TodoItem::new(title.to_string(), group_id.clone(), order);
+        let description = cell(description_column);
+        if !description.is_empty() {
+            todo.details = Some(description.to_string());
+        }
+        todo.color_tag = color_tag_from_priority(cell(priority_column).parse().unwrap_or(4));
+        todo.priority = Priority::from(todo.color_tag);
+        todo.parent_id = parents.last().cloned();
+
+        let date = cell(date_column);
+        if !date.is_empty() {
+            match parse_date_time(date) {
+                Some(date_time) => {
+                    todo.due_date = Some(date_time);
+                    todo.time_nodes.push(TimeNode::new(date_time, Some("due".to_string())));
+                }
+                None => summary.unmapped.push(format!("\"{}\": due date \"{}\" not recognised", title, date)),
+            }
+        }
+
+        if parents.len() == indent - 1 {
+            parents.push(todo.id.clone());
+        }
+        data.todos.push(todo);
+        summary.todos_imported += 1;
+    }
+
+    Ok(summary)
+}
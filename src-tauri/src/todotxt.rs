@@ -0,0 +1,380 @@
+use crate::csv;
+use crate::models::*;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+
+// todo.txt 优先级 (A)-(D) 对应显式优先级，没有优先级时不写；
+// 颜色标签另存为 color: 扩展字段
+const PRIORITY_LETTERS: [(char, Priority); 4] = [
+    ('A', Priority::Urgent),
+    ('B', Priority::High),
+    ('C', Priority::Medium),
+    ('D', Priority::Low),
+];
+
+fn priority_letter(priority: Priority) -> Option<char> {
+    PRIORITY_LETTERS.iter()
+        .find(|(_, p)| *p == priority)
+        .map(|(letter, _)| *letter)
+}
+
+fn priority_from_letter(letter: char) -> Priority {
+    PRIORITY_LETTERS.iter()
+        .find(|(l, _)| *l == letter)
+        .map(|(_, p)| *p)
+        // E-Z 统一视为低优先级，原字母以 pri:X 保存在详情中
+        .unwrap_or(Priority::Low)
+}
+
+// 导入时保存在详情中的原字母，优先级没有改动过时照原样导出
+fn exported_priority_letter(todo: &TodoItem) -> Option<char> {
+    let kept = todo.details.iter()
+        .flat_map(|d| d.lines())
+        .filter_map(|line| line.trim().strip_prefix("pri:"))
+        .filter_map(parse_letter)
+        .find(|letter| priority_from_letter(*letter) == todo.priority);
+    kept.or_else(|| priority_letter(todo.priority))
+}
+
+fn format_date(date_time: &DateTime<Utc>) -> String {
+    date_time.with_timezone(&Local).format("%Y-%m-%d").to_string()
+}
+
+fn parse_date(token: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(token, "%Y-%m-%d").ok()?;
+    Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+fn parse_letter(value: &str) -> Option<char> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_ascii_uppercase() => Some(letter),
+        _ => None,
+    }
+}
+
+fn parse_priority(token: &str) -> Option<char> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some('('), Some(letter), Some(')'), None) if letter.is_ascii_uppercase() => Some(letter),
+        _ => None,
+    }
+}
+
+// key:value 扩展字段，key 以字母开头；URL（含 ://）和 "10:30" 这样的时刻不算扩展
+fn parse_extension(token: &str) -> Option<(&str, &str)> {
+    if token.contains("://") {
+        return None;
+    }
+    let (key, value) = token.split_once(':')?;
+    let valid_key = key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    if valid_key && !value.is_empty() {
+        Some((key, value))
+    } else {
+        None
+    }
+}
+
+// 分组名中的空格在 +project 中以下划线表示
+fn project_token(group_name: &str) -> String {
+    group_name.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+fn due_time_node(todo: &TodoItem) -> Option<&TimeNode> {
+    todo.time_nodes.iter()
+        .find(|tn| tn.description.as_deref() == Some("due"))
+        .or_else(|| todo.time_nodes.iter().max_by_key(|tn| tn.date_time))
+}
+
+/// 导出为 todo.txt 文本，每个未归档的待办一行。
+pub fn export(data: &AppData) -> String {
+    let todos = data.sort_in_tree(data.todos.iter().filter(|t| !t.archived).cloned().collect());
+
+    let mut lines = Vec::new();
+    for todo in &todos {
+        let mut parts: Vec<String> = Vec::new();
+
+        if todo.completed {
+            parts.push("x".to_string());
+            parts.push(format_date(&todo.completed_at.unwrap_or(todo.updated_at)));
+            parts.push(format_date(&todo.created_at));
+        } else {
+            if let Some(letter) = exported_priority_letter(todo) {
+                parts.push(format!("({})", letter));
+            }
+            parts.push(format_date(&todo.created_at));
+        }
+
+        parts.push(todo.title.split_whitespace().collect::<Vec<_>>().join(" "));
+
+        if let Some(group) = data.groups.iter().find(|g| g.id == todo.group_id) {
+            parts.push(format!("+{}", project_token(&group.name)));
+        }
+        for tag in &todo.tags {
+            parts.push(format!("@{}", project_token(tag)));
+        }
+        match (todo.due_date, due_time_node(todo)) {
+            (Some(due_date), _) => parts.push(format!("due:{}", format_date(&due_date))),
+            (None, Some(node)) => parts.push(format!("due:{}", format_date(&node.date_time))),
+            (None, None) => {}
+        }
+        // t: 为 todo.txt 约定的开始（threshold）日期
+        if let Some(start_date) = &todo.start_date {
+            parts.push(format!("t:{}", format_date(start_date)));
+        }
+        // 已完成的待办按惯例把优先级写成 pri:X
+        if todo.completed {
+            if let Some(letter) = exported_priority_letter(todo) {
+                parts.push(format!("pri:{}", letter));
+            }
+        }
+        if todo.color_tag != ColorTag::default() {
+            parts.push(format!("color:{:?}", todo.color_tag).to_lowercase());
+        }
+        if todo.hidden {
+            parts.push("h:1".to_string());
+        }
+
+        // 详情中整行都是 key:value 的部分原样写回；pri: 已在上面写出
+        if let Some(details) = &todo.details {
+            for line in details.lines() {
+                let tokens: Vec<&str> = line.split_whitespace().collect();
+                if !tokens.is_empty() && tokens.iter().all(|t| parse_extension(t).is_some()) {
+                    parts.extend(tokens.iter().filter(|t| !t.starts_with("pri:")).map(|t| t.to_string()));
+                }
+            }
+        }
+
+        lines.push(parts.join(" "));
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+/// 标题中形如 key:value 的词导入时会被当作扩展字段移出标题，导出前列出这些待办
+pub fn misread_titles(data: &AppData) -> Vec<String> {
+    data.todos.iter()
+        .filter(|t| !t.archived)
+        .filter_map(|t| {
+            let tokens: Vec<&str> = t.title.split_whitespace()
+                .filter(|token| parse_extension(token).is_some())
+                .collect();
+            if tokens.is_empty() {
+                None
+            } else {
+                Some(format!("\"{}\": {} will be read back as an extension", t.title, tokens.join(" ")))
+            }
+        })
+        .collect()
+}
+
+/// 解析 todo.txt 文本并追加到现有数据中。
+pub fn import(content: &str, data: &mut AppData) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let default_group_id = data.default_group_id();
+
+    for line in content.lines() {
+        let mut tokens = line.split_whitespace().peekable();
+        if tokens.peek().is_none() {
+            continue;
+        }
+
+        let mut completed = false;
+        let mut completed_at = None;
+        let mut created_at = None;
+        let mut letter = None;
+        let mut color_tag = ColorTag::default();
+
+        if tokens.peek() == Some(&"x") {
+            completed = true;
+            tokens.next();
+            if let Some(date) = tokens.peek().and_then(|t| parse_date(t)) {
+                completed_at = Some(date);
+                tokens.next();
+            }
+        } else if let Some(value) = tokens.peek().and_then(|t| parse_priority(t)) {
+            letter = Some(value);
+            tokens.next();
+        }
+        if let Some(date) = tokens.peek().and_then(|t| parse_date(t)) {
+            created_at = Some(date);
+            tokens.next();
+        }
+
+        // 多个 +project 时，最后一个作为分组，其余保留在标题中
+        let rest: Vec<&str> = tokens.collect();
+        let project_index = rest.iter().rposition(|t| t.len() > 1 && t.starts_with('+'));
+        let project = project_index.map(|i| &rest[i][1..]);
+
+        let mut words: Vec<&str> = Vec::new();
+        let mut tags: Vec<String> = Vec::new();
+        let mut due = None;
+        let mut start = None;
+        let mut hidden = false;
+        let mut extensions: Vec<&str> = Vec::new();
+
+        for (index, token) in rest.iter().copied().enumerate() {
+            if Some(index) == project_index {
+                continue;
+            }
+            // @context 作为标签
+            if let Some(context) = token.strip_prefix('@').filter(|c| !c.is_empty()) {
+                tags.push(context.replace('_', " "));
+                continue;
+            }
+            match parse_extension(token) {
+                Some(("due", value)) => match parse_date(value) {
+                    Some(date) => due = Some(date),
+                    None => extensions.push(token),
+                },
+                Some(("t", value)) => match parse_date(value) {
+                    Some(date) => start = Some(date),
+                    None => extensions.push(token),
+                },
+                Some(("pri", value)) if completed && parse_letter(value).is_some() => {
+                    letter = parse_letter(value);
+                }
+                Some(("color", value)) => match csv::parse_color_tag(value) {
+                    Some(tag) => color_tag = tag,
+                    None => extensions.push(token),
+                },
+                Some(("h", "1")) => hidden = true,
+                Some(_) => extensions.push(token),
+                None => words.push(token),
+            }
+        }
+
+        let group_id = match project {
+            Some(name) => {
+                let existing = data.groups.iter()
+                    .find(|g| g.name == name || project_token(&g.name) == name)
+                    .map(|g| g.id.clone());
+                existing.unwrap_or_else(|| {
+                    let (id, created) = data.find_or_create_group(&name.replace('_', " "));
+                    if created {
+                        summary.groups_created += 1;
+                    }
+                    id
+                })
+            }
+            None => default_group_id.clone(),
+        };
+
+        let order = data.next_todo_order(&group_id, None);
+        let mut todo = TodoItem::new(words.join(" "), group_id, order);
+        todo.color_tag = color_tag;
+        todo.priority = letter.map(priority_from_letter).unwrap_or_default();
+        todo.start_date = start;
+        todo.due_date = due;
+        todo.completed = completed;
+        todo.hidden = hidden;
+        todo.tags = normalize_tags(tags);
+        if let Some(created_at) = created_at {
+            todo.created_at = created_at;
+        }
+        todo.updated_at = completed_at.unwrap_or(todo.created_at);
+        if completed {
+            todo.completed_at = Some(todo.updated_at);
+        }
+
+        // 没有对应优先级的字母保存下来，导出时照原样写回
+        let mut extensions: Vec<String> = extensions.into_iter().map(|e| e.to_string()).collect();
+        if let Some(letter) = letter.filter(|l| priority_letter(priority_from_letter(*l)) != Some(*l)) {
+            extensions.insert(0, format!("pri:{}", letter));
+        }
+        if !extensions.is_empty() {
+            todo.details = Some(extensions.join("\n"));
+        }
+        if let Some(date_time) = due {
+            todo.time_nodes.push(TimeNode::new(date_time, Some("due".to_string())));
+        }
+
+        data.todos.push(todo);
+        summary.todos_imported += 1;
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo<'a>(data: &'a AppData, title: &str) -> &'a TodoItem {
+        data.todos.iter().find(|t| t.title == title).unwrap()
+    }
+
+    #[test]
+    fn round_trip_keeps_every_field() {
+        let content = "\
+(A) 2024-01-02 Call mom +Family_Stuff @phone due:2024-02-01 t:2024-01-30 rec:1w pm:2
+(K) 2024-01-03 Someday maybe +Family_Stuff
+(B) 2024-01-04 Paint the fence +Family_Stuff color:red1 h:1
+x 2024-01-05 2024-01-01 Pay bills +Family_Stuff pri:M
+x 2024-01-06 2024-01-02 Plain done +Family_Stuff
+";
+        let mut data = AppData::default();
+        let summary = import(content, &mut data);
+        assert_eq!(summary.todos_imported, 5);
+        assert_eq!(summary.groups_created, 1);
+
+        // 下划线还原为分组名中的空格
+        let group = data.groups.iter().find(|g| g.name == "Family Stuff").unwrap();
+        assert!(data.todos.iter().all(|t| t.group_id == group.id));
+
+        let call = todo(&data, "Call mom");
+        assert_eq!(call.priority, Priority::Urgent);
+        assert_eq!(call.tags, vec!["phone".to_string()]);
+        assert_eq!(call.due_date, parse_date("2024-02-01"));
+        assert_eq!(call.start_date, parse_date("2024-01-30"));
+        assert_eq!(call.created_at, parse_date("2024-01-02").unwrap());
+        assert_eq!(call.details.as_deref(), Some("rec:1w\npm:2"));
+
+        let someday = todo(&data, "Someday maybe");
+        assert_eq!(someday.priority, Priority::Low);
+        assert_eq!(someday.details.as_deref(), Some("pri:K"));
+
+        let paint = todo(&data, "Paint the fence");
+        assert_eq!(paint.priority, Priority::High);
+        assert_eq!(paint.color_tag, ColorTag::Red1);
+        assert!(paint.hidden);
+
+        let bills = todo(&data, "Pay bills");
+        assert!(bills.completed);
+        assert_eq!(bills.completed_at, parse_date("2024-01-05"));
+        assert_eq!(bills.priority, Priority::Low);
+
+        assert_eq!(todo(&data, "Plain done").priority, Priority::None);
+
+        assert_eq!(export(&data), content);
+    }
+
+    #[test]
+    fn export_follows_edited_priority() {
+        let mut data = AppData::default();
+        import("(K) 2024-01-03 Someday maybe\n", &mut data);
+        data.todos[0].priority = Priority::High;
+        data.todos[0].color_tag = ColorTag::Purple7;
+
+        let line = export(&data);
+        assert!(line.starts_with("(B) 2024-01-03 Someday maybe"), "{}", line);
+        assert!(line.contains("color:purple7"));
+        assert!(!line.contains("pri:K"));
+    }
+
+    #[test]
+    fn colon_words_stay_in_title() {
+        let mut data = AppData::default();
+        import("Call at 10:30 see https://example.com/a:b\n", &mut data);
+        assert_eq!(data.todos[0].title, "Call at 10:30 see https://example.com/a:b");
+        assert_eq!(data.todos[0].details, None);
+        assert!(misread_titles(&data).is_empty());
+
+        data.todos[0].title = "Note:x for later".to_string();
+        assert_eq!(misread_titles(&data).len(), 1);
+    }
+}
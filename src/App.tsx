@@ -1,5 +1,5 @@
 import { useEffect, useState, useRef, useCallback } from "react";
-import { AppData, Theme, AppSettings, DockedEdge, ColorTag, TimeNode } from "./types";
+import { AppData, Theme, AppSettings, DockedEdge, ColorTag, TimeNode, DataChanged } from "./types";
 import { api } from "./services/api";
 import Sidebar from "./components/Sidebar";
 import TodoList from "./components/TodoList";
@@ -43,6 +43,38 @@ function App() {
     loadData();
   }, []);
 
+  // 后端数据变化时只重新获取涉及的待办和分组
+  useEffect(() => {
+    const applyChange = async (change: DataChanged) => {
+      if (change.reload) {
+        await loadData();
+        return;
+      }
+      // 一次调用取回所有变化的待办
+      const fetched = change.todo_ids.length > 0 ? await api.getTodos(change.todo_ids) : [];
+      const groups = change.group_ids.length > 0 ? await api.listGroups() : null;
+
+      setAppData((prev) => {
+        if (!prev) return prev;
+        // 查不到的已被删除，归档的不在主列表中
+        const changed = new Set(change.todo_ids);
+        const kept = prev.todos.filter((t) => !changed.has(t.id));
+        return {
+          ...prev,
+          todos: [...kept, ...fetched.filter((t) => !t.archived)],
+          groups: groups ?? prev.groups,
+        };
+      });
+    };
+
+    const unlisten = api.onDataChanged((change) => {
+      applyChange(change).catch((error) => console.error("Failed to apply data change:", error));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // 在数据加载完成后初始化停靠边和窗口位置
   useEffect(() => {
     if (appData) {
@@ -246,8 +278,8 @@ function App() {
   }) => {
     if (!appData) return;
     try {
-      // 创建待办
-      let newTodo = await api.createTodo(data.title, data.details, data.groupId, data.colorTag);
+      // 创建待办；列表通过 "data-changed" 事件更新
+      const newTodo = await api.createTodo(data.title, data.details, data.groupId, data.colorTag);
 
       // 添加时间节点
       for (const timeNode of data.timeNodes) {
@@ -259,20 +291,6 @@ function App() {
           timeNode.reminder_minutes_before
         );
       }
-
-      // 重新获取待办，以获取添加的时间节点
-      if (data.timeNodes.length > 0) {
-        const refreshedData = await api.getAllData();
-        const refreshedTodo = refreshedData.todos.find(t => t.id === newTodo.id);
-        if (refreshedTodo) {
-          newTodo = refreshedTodo;
-        }
-      }
-
-      setAppData({
-        ...appData,
-        todos: [...appData.todos, newTodo],
-      });
     } catch (error) {
       console.error("Failed to create todo:", error);
     }
@@ -309,11 +327,8 @@ function App() {
       });
       console.log('Basic fields updated successfully');
 
-      // 如果只是更新completed状态，跳过时间节点处理
+      // 如果只是更新completed状态，跳过时间节点处理；列表通过 "data-changed" 事件更新
       if (!updates.timeNodes) {
-        console.log('No time nodes to update, refreshing data...');
-        const refreshedData = await api.getAllData();
-        setAppData(refreshedData);
         console.log('=== handleUpdateTodo COMPLETE (simple update) ===');
         return;
       }
@@ -360,39 +375,6 @@ function App() {
         }
       }
 
-      // 第5步：重新获取完整数据
-      console.log('Step 5: Refreshing all data...');
-      const refreshedData = await api.getAllData();
-      const refreshedTodo = refreshedData.todos.find(t => t.id === id);
-
-      if (!refreshedTodo) {
-        console.error('Todo not found after refresh:', id);
-        return;
-      }
-
-      console.log('Refreshed todo:', refreshedTodo);
-
-      // 采用删除-重建策略：先从状态中移除旧项，再添加新项
-      // 这样强制React完全重新渲染组件，避免状态缓存问题
-      console.log('Step 6: Removing old todo from state...');
-      const todosWithoutOld = refreshedData.todos.filter(t => t.id !== id);
-
-      console.log('Step 7: Re-adding updated todo...');
-      const reorderedTodos = [...todosWithoutOld];
-      // 找到原来的位置，插入更新后的todo
-      const originalIndex = appData.todos.findIndex(t => t.id === id);
-      if (originalIndex !== -1) {
-        reorderedTodos.splice(originalIndex, 0, refreshedTodo);
-      } else {
-        reorderedTodos.push(refreshedTodo);
-      }
-
-      console.log('Step 8: Setting new state with rebuilt todo list...');
-      setAppData({
-        ...refreshedData,
-        todos: reorderedTodos
-      });
-
       console.log('=== handleUpdateTodo COMPLETE ===');
     } catch (error) {
       console.error('=== handleUpdateTodo ERROR ===');
@@ -404,10 +386,6 @@ function App() {
     if (!appData) return;
     try {
       await api.deleteTodo(id);
-      setAppData({
-        ...appData,
-        todos: appData.todos.filter((t) => t.id !== id),
-      });
     } catch (error) {
       console.error("Failed to delete todo:", error);
     }
@@ -416,11 +394,7 @@ function App() {
   const handleCreateGroup = async (name: string) => {
     if (!appData) return;
     try {
-      const newGroup = await api.createGroup(name);
-      setAppData({
-        ...appData,
-        groups: [...appData.groups, newGroup],
-      });
+      await api.createGroup(name);
     } catch (error) {
       console.error("Failed to create group:", error);
     }
@@ -429,29 +403,27 @@ function App() {
   const handleUpdateGroup = async (id: string, name: string) => {
     if (!appData) return;
     try {
-      const updatedGroup = await api.updateGroup(id, name);
-      setAppData({
-        ...appData,
-        groups: appData.groups.map((g) => (g.id === id ? updatedGroup : g)),
-      });
+      await api.updateGroup(id, name);
     } catch (error) {
       console.error("Failed to update group:", error);
     }
   };
 
+  const handleSetDefaultGroup = async (id: string) => {
+    try {
+      // 新旧默认分组通过 "data-changed" 事件更新
+      await api.setDefaultGroup(id);
+    } catch (error) {
+      console.error("Failed to set default group:", error);
+    }
+  };
+
   const handleDeleteGroup = async (id: string) => {
     if (!appData) return;
     try {
-      // moveToPersonal=true 表示将该分组的待办移动到 personal 分组
+      // moveTodos=true 表示将该分组的待办移动到默认分组
+      // 删除的子分组和移动的待办通过 "data-changed" 事件更新
       await api.deleteGroup(id, true);
-      setAppData({
-        ...appData,
-        groups: appData.groups.filter((g) => g.id !== id),
-        // 同时更新待办列表，将删除分组的待办移到 personal
-        todos: appData.todos.map((t) =>
-          t.group_id === id ? { ...t, group_id: "personal" } : t
-        ),
-      });
       // 如果当前选中的分组被删除，切换到“所有”视图
       if (selectedGroupId === id) {
         setSelectedView("all");
@@ -792,8 +764,8 @@ function App() {
               onCreateGroup={handleCreateGroup}
               onUpdateGroup={handleUpdateGroup}
               onDeleteGroup={handleDeleteGroup}
+              onSetDefaultGroup={handleSetDefaultGroup}
               onCollapseChange={setSidebarCollapsed}
-              onGroupsReordered={loadData}
             />
             <div className="flex-1 overflow-auto">
               {selectedView === "settings" ? (
@@ -813,7 +785,6 @@ function App() {
                   onCreateTodo={handleCreateTodo}
                   onUpdateTodo={handleUpdateTodo}
                   onDeleteTodo={handleDeleteTodo}
-                  onTodosReordered={loadData}
                   onDragStart={() => setIsDraggingTodo(true)}
                   onDragEnd={async () => {
                     // 拖动结束后，根据情况决定是否重置状态
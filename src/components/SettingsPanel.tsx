@@ -176,7 +176,9 @@ function SettingsPanel({ settings, onUpdateSettings, sidebarCollapsed = false }:
       });
       
       if (selected && typeof selected === "string") {
-        await api.importData(selected);
+        // 部分导出（如单个分组）合并到现有数据，完整导出才替换
+        const info = await api.readExportInfo(selected);
+        await api.importData(selected, info.partial);
         await dialog.message("数据导入成功！请重启应用以查看导入的数据。", { title: "成功", type: "info" });
         // 重新加载页面以显示导入的数据
         window.location.reload();
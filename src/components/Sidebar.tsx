@@ -32,6 +32,7 @@ interface SidebarProps {
   onCreateGroup: (name: string) => void;
   onUpdateGroup?: (id: string, name: string) => void;
   onDeleteGroup?: (id: string) => void;
+  onSetDefaultGroup?: (id: string) => void;
   onCollapseChange?: (collapsed: boolean) => void;
   onGroupsReordered?: () => void;  // 分组排序后刷新回调
 }
@@ -50,6 +51,7 @@ function Sidebar({
   onCreateGroup,
   onUpdateGroup,
   onDeleteGroup,
+  onSetDefaultGroup,
   onCollapseChange,
   onGroupsReordered,
 }: SidebarProps) {
@@ -99,7 +101,10 @@ function Sidebar({
     const reorderedCustomGroups = arrayMove(customGroups, oldIndex, newIndex);
     // 合并默认分组和重新排序的自定义分组
     const allReorderedGroups = [...defaultGroups, ...reorderedCustomGroups];
-    const newOrderIds = allReorderedGroups.map((group) => group.id);
+    // 后端按上级分组调整顺序，这里只提交顶层分组
+    const newOrderIds = allReorderedGroups
+      .filter((group) => !group.parent_group_id)
+      .map((group) => group.id);
     
     console.log('Groups reordered:', { oldIndex, newIndex, newOrderIds });
 
@@ -152,6 +157,13 @@ function Sidebar({
     setDialogOpen(true);
   };
 
+  const handleSetDefaultGroup = (id: string, e: React.MouseEvent) => {
+    e.stopPropagation();
+    if (onSetDefaultGroup) {
+      onSetDefaultGroup(id);
+    }
+  };
+
   const handleDialogConfirm = () => {
     if (dialogType === 'create' && inputValue.trim()) {
       onCreateGroup(inputValue.trim());
@@ -222,6 +234,7 @@ function Sidebar({
               onSelect={() => onSelectGroup(group.id)}
               onEdit={(e) => handleEditGroup(group.id, group.name, e)}
               onDelete={(e) => handleDeleteGroup(group.id, group.name, e)}
+              onSetDefault={onSetDefaultGroup ? (e) => handleSetDefaultGroup(group.id, e) : undefined}
               collapsed={collapsed}
             />
           ))}
@@ -244,6 +257,7 @@ function Sidebar({
                   onSelect={() => onSelectGroup(group.id)}
                   onEdit={(e) => handleEditGroup(group.id, group.name, e)}
                   onDelete={(e) => handleDeleteGroup(group.id, group.name, e)}
+                  onSetDefault={onSetDefaultGroup ? (e) => handleSetDefaultGroup(group.id, e) : undefined}
                   collapsed={collapsed}
                 />
               ))}
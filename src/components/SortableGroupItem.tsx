@@ -1,6 +1,6 @@
 import { useSortable } from "@dnd-kit/sortable";
 import { CSS } from "@dnd-kit/utilities";
-import { Edit2, Trash2 } from "lucide-react";
+import { Edit2, Star, Trash2 } from "lucide-react";
 import { TodoGroup } from "../types";
 
 interface SortableGroupItemProps {
@@ -9,6 +9,7 @@ interface SortableGroupItemProps {
   onSelect: () => void;
   onEdit: (e: React.MouseEvent) => void;
   onDelete: (e: React.MouseEvent) => void;
+  onSetDefault?: (e: React.MouseEvent) => void;
   collapsed?: boolean;  // 导航栏是否折叠
 }
 
@@ -18,6 +19,7 @@ function SortableGroupItem({
   onSelect,
   onEdit,
   onDelete,
+  onSetDefault,
   collapsed = false,
 }: SortableGroupItemProps) {
   const {
@@ -75,8 +77,9 @@ function SortableGroupItem({
         </div>
         {!collapsed && <span className="text-sm truncate">{group.name}</span>}
       </div>
-      {!collapsed && !group.is_default && (
+      {!collapsed && (
         <div className="flex items-center gap-1 opacity-0 group-hover:opacity-100 transition-opacity">
+          {/* 默认分组可以重命名，但不能删除 */}
           <button
             onClick={onEdit}
             className="p-1 hover:bg-gray-200 rounded"
@@ -84,13 +87,24 @@ function SortableGroupItem({
           >
             <Edit2 className="w-3 h-3" />
           </button>
-          <button
-            onClick={onDelete}
-            className="p-1 hover:bg-red-100 rounded text-red-500"
-            title="删除分组"
-          >
-            <Trash2 className="w-3 h-3" />
-          </button>
+          {!group.is_default && onSetDefault && (
+            <button
+              onClick={onSetDefault}
+              className="p-1 hover:bg-gray-200 rounded"
+              title="设为默认分组"
+            >
+              <Star className="w-3 h-3" />
+            </button>
+          )}
+          {!group.is_default && (
+            <button
+              onClick={onDelete}
+              className="p-1 hover:bg-red-100 rounded text-red-500"
+              title="删除分组"
+            >
+              <Trash2 className="w-3 h-3" />
+            </button>
+          )}
         </div>
       )}
     </div>
@@ -21,6 +21,57 @@ import {
   verticalListSortingStrategy,
 } from "@dnd-kit/sortable";
 
+// 按分组树、父子关系和兄弟顺序排列，子待办紧跟在父待办之后；
+// order 只在同一分组、同一父待办的兄弟之间有意义
+function sortTodosInTree(todos: TodoItem[], groups: TodoGroup[]): TodoItem[] {
+  const groupRank = new Map<string, number>();
+  const visitGroups = (parentId: string | undefined) => {
+    groups
+      .filter((g) => (g.parent_group_id ?? undefined) === parentId && !groupRank.has(g.id))
+      .sort((a, b) => a.order - b.order)
+      .forEach((g) => {
+        groupRank.set(g.id, groupRank.size);
+        visitGroups(g.id);
+      });
+  };
+  const groupIds = new Set(groups.map((g) => g.id));
+  groups
+    .filter((g) => !g.parent_group_id || !groupIds.has(g.parent_group_id))
+    .sort((a, b) => a.order - b.order)
+    .forEach((g) => {
+      if (!groupRank.has(g.id)) {
+        groupRank.set(g.id, groupRank.size);
+        visitGroups(g.id);
+      }
+    });
+
+  const byId = new Map(todos.map((todo) => [todo.id, todo]));
+  // 从最上层祖先到自身的路径
+  const pathOf = (todo: TodoItem): TodoItem[] => {
+    const path = [todo];
+    let parent = todo.parent_id ? byId.get(todo.parent_id) : undefined;
+    while (parent && !path.includes(parent)) {
+      path.unshift(parent);
+      parent = parent.parent_id ? byId.get(parent.parent_id) : undefined;
+    }
+    return path;
+  };
+  const paths = new Map(todos.map((todo) => [todo.id, pathOf(todo)]));
+
+  return [...todos].sort((a, b) => {
+    const rankDiff = (groupRank.get(a.group_id) ?? Infinity) - (groupRank.get(b.group_id) ?? Infinity);
+    if (rankDiff !== 0 && !Number.isNaN(rankDiff)) return rankDiff;
+    const pathA = paths.get(a.id)!;
+    const pathB = paths.get(b.id)!;
+    for (let i = 0; i < Math.min(pathA.length, pathB.length); i++) {
+      if (pathA[i].id !== pathB[i].id) {
+        return pathA[i].order - pathB[i].order || pathA[i].id.localeCompare(pathB[i].id);
+      }
+    }
+    return pathA.length - pathB.length;
+  });
+}
+
 interface TodoListProps {
   todos: TodoItem[];
   groups: TodoGroup[];
@@ -66,8 +117,8 @@ function TodoList({
     })
   );
   
-  // 按order排序获取待办列表
-  const sortedTodos = [...todos].sort((a, b) => a.order - b.order);
+  // 按分组树和父子关系排序获取待办列表
+  const sortedTodos = sortTodosInTree(todos, groups);
 
   const handleCreate = (data: {
     title: string;
@@ -116,13 +167,18 @@ function TodoList({
 
     // 使用dnd-kit的arrayMove重新排序
     const reorderedTodos = arrayMove(sortedTodos, oldIndex, newIndex);
-    const newOrderIds = reorderedTodos.map((todo) => todo.id);
+    const moved = sortedTodos[oldIndex];
+    // 只和同一分组、同一父待办的兄弟比较，放到前面最近的兄弟之后
+    const afterTodo = reorderedTodos
+      .slice(0, newIndex)
+      .reverse()
+      .find((todo) => todo.group_id === moved.group_id && todo.parent_id === moved.parent_id);
     
-    console.log('Reordered:', { oldIndex, newIndex, newOrderIds });
+    console.log('Reordered:', { oldIndex, newIndex, afterId: afterTodo?.id });
 
     try {
-      // 调用API保存新顺序
-      await api.reorderTodos(newOrderIds);
+      // 调用API保存新位置，只改动被拖动的待办
+      await api.repositionTodo(moved.id, afterTodo?.id);
       // 调用回调刷新数据（保持所有状态不变，包括isPinned）
       if (onTodosReordered) {
         await onTodosReordered();
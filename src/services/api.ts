@@ -1,10 +1,34 @@
 import { invoke } from "@tauri-apps/api/tauri";
-import { TodoItem, TodoGroup, AppData, AppSettings, ColorTag, TimeNode, WindowPosition, DockedEdge } from "../types";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { TodoItem, TodoGroup, AppData, AppSettings, ColorTag, TimeNode, WindowPosition, DockedEdge, ImportSummary, CsvColumnMapping, ExportOptions, ReportOptions, TodoFilter, TagCount, Priority, SortKey, TimeEntry, TimeTotals, PomodoroStatus, FocusStats, EffortRollup, GroupEffortSummary, Stats, StatsRange, HistoryEntry, Attachment, TodoTemplate, DuplicateOptions, CustomFieldDef, CustomFieldValue, GroupDefaults, ArchivedPage, DataChanged, ExportInfo } from "../types";
 
 export const api = {
-  // 获取所有数据
-  getAllData: async (): Promise<AppData> => {
-    return await invoke("get_all_data");
+  // 获取所有数据，默认不含归档的待办
+  getAllData: async (includeArchived?: boolean): Promise<AppData> => {
+    return await invoke("get_all_data", { includeArchived });
+  },
+
+  // 按需获取：未传筛选条件时不含归档的待办
+  listTodos: async (groupId?: string, filter?: TodoFilter): Promise<TodoItem[]> => {
+    return await invoke("list_todos", { groupId, filter });
+  },
+
+  getTodo: async (id: string): Promise<TodoItem> => {
+    return await invoke("get_todo", { id });
+  },
+
+  // 批量获取，已删除的 id 不在结果中
+  getTodos: async (ids: string[]): Promise<TodoItem[]> => {
+    return await invoke("get_todos", { ids });
+  },
+
+  listGroups: async (): Promise<TodoGroup[]> => {
+    return await invoke("list_groups");
+  },
+
+  // 订阅数据变更，返回取消订阅的函数
+  onDataChanged: async (handler: (change: DataChanged) => void): Promise<UnlistenFn> => {
+    return await listen<DataChanged>("data-changed", (event) => handler(event.payload));
   },
 
   // 待办事项操作
@@ -12,13 +36,28 @@ export const api = {
     title: string,
     details: string | null,
     groupId: string,
-    colorTag: ColorTag
+    // 不传时使用分组的默认颜色
+    colorTag?: ColorTag,
+    tags?: string[],
+    priority?: Priority,
+    startDate?: string,
+    dueDate?: string,
+    estimateMinutes?: number,
+    parentId?: string,
+    customFields?: Record<string, CustomFieldValue>
   ): Promise<TodoItem> => {
     return await invoke("create_todo", {
       title,
       details,
       groupId,
       colorTag,
+      tags,
+      priority,
+      startDate,
+      dueDate,
+      estimateMinutes,
+      parentId,
+      customFields,
     });
   },
 
@@ -32,6 +71,17 @@ export const api = {
       completed?: boolean;
       hidden?: boolean;
       archived?: boolean;
+      tags?: string[];
+      priority?: Priority;
+      // 传空字符串清除日期
+      startDate?: string;
+      dueDate?: string;
+      // 传 0 清除预估
+      estimateMinutes?: number;
+      // 传空字符串移到顶层
+      parentId?: string;
+      // 整体替换自定义字段取值
+      customFields?: Record<string, CustomFieldValue>;
     }
   ): Promise<TodoItem> => {
     return await invoke("update_todo", {
@@ -43,6 +93,13 @@ export const api = {
       completed: updates.completed,
       hidden: updates.hidden,
       archived: updates.archived,
+      tags: updates.tags,
+      priority: updates.priority,
+      startDate: updates.startDate,
+      dueDate: updates.dueDate,
+      estimateMinutes: updates.estimateMinutes,
+      parentId: updates.parentId,
+      customFields: updates.customFields,
     });
   },
 
@@ -50,17 +107,188 @@ export const api = {
     return await invoke("delete_todo", { id });
   },
 
+  // 归档浏览：按归档时间从新到旧分页，from/to 为 RFC3339 时间
+  listArchived: async (
+    options: { groupId?: string; from?: string; to?: string; page?: number; pageSize?: number } = {}
+  ): Promise<ArchivedPage> => {
+    return await invoke("list_archived", options);
+  },
+
+  unarchiveTodos: async (ids: string[]): Promise<TodoItem[]> => {
+    return await invoke("unarchive_todos", { ids });
+  },
+
+  // 永久删除，只对已归档的待办生效
+  purgeArchived: async (ids: string[]): Promise<string[]> => {
+    return await invoke("purge_archived", { ids });
+  },
+
+  // 依赖关系
+  addDependency: async (todoId: string, blockerId: string): Promise<TodoItem> => {
+    return await invoke("add_dependency", { todoId, blockerId });
+  },
+
+  removeDependency: async (todoId: string, blockerId: string): Promise<TodoItem> => {
+    return await invoke("remove_dependency", { todoId, blockerId });
+  },
+
+  getReadyTodos: async (groupId?: string, includeSubgroups?: boolean): Promise<TodoItem[]> => {
+    return await invoke("get_ready_todos", { groupId, includeSubgroups });
+  },
+
+  getBlockedTodos: async (groupId?: string, includeSubgroups?: boolean): Promise<TodoItem[]> => {
+    return await invoke("get_blocked_todos", { groupId, includeSubgroups });
+  },
+
+  // 附件
+  addFileAttachment: async (todoId: string, path: string, copy: boolean): Promise<Attachment> => {
+    return await invoke("add_file_attachment", { todoId, path, copy });
+  },
+
+  addUrlAttachment: async (todoId: string, url: string, title?: string): Promise<Attachment> => {
+    return await invoke("add_url_attachment", { todoId, url, title });
+  },
+
+  addTodoReference: async (todoId: string, targetId: string): Promise<Attachment> => {
+    return await invoke("add_todo_reference", { todoId, targetId });
+  },
+
+  removeAttachment: async (todoId: string, attachmentId: string): Promise<void> => {
+    return await invoke("remove_attachment", { todoId, attachmentId });
+  },
+
+  openAttachment: async (todoId: string, attachmentId: string): Promise<void> => {
+    return await invoke("open_attachment", { todoId, attachmentId });
+  },
+
+  // 模板
+  listTemplates: async (): Promise<TodoTemplate[]> => {
+    return await invoke("list_templates");
+  },
+
+  createTemplateFromTodo: async (todoId: string, name: string): Promise<TodoTemplate> => {
+    return await invoke("create_template_from_todo", { todoId, name });
+  },
+
+  updateTemplate: async (template: TodoTemplate): Promise<TodoTemplate> => {
+    return await invoke("update_template", { template });
+  },
+
+  deleteTemplate: async (id: string): Promise<void> => {
+    return await invoke("delete_template", { id });
+  },
+
+  instantiateTemplate: async (templateId: string, groupId: string, baseDate: string): Promise<TodoItem[]> => {
+    return await invoke("instantiate_template", { templateId, groupId, baseDate });
+  },
+
+  getTodoHistory: async (id: string): Promise<HistoryEntry[]> => {
+    return await invoke("get_todo_history", { id });
+  },
+
+  // 计时
+  startTimer: async (todoId: string, note?: string): Promise<TimeEntry> => {
+    return await invoke("start_timer", { todoId, note });
+  },
+
+  stopTimer: async (note?: string): Promise<TimeEntry | null> => {
+    return await invoke("stop_timer", { note });
+  },
+
+  getRunningTimer: async (): Promise<TimeEntry | null> => {
+    return await invoke("get_running_timer");
+  },
+
+  listTimeEntries: async (todoId?: string, from?: string, to?: string): Promise<TimeEntry[]> => {
+    return await invoke("list_time_entries", { todoId, from, to });
+  },
+
+  getTimeTotals: async (from?: string, to?: string): Promise<TimeTotals> => {
+    return await invoke("get_time_totals", { from, to });
+  },
+
+  // 工作量
+  getEffortRollup: async (todoId: string): Promise<EffortRollup> => {
+    return await invoke("get_effort_rollup", { todoId });
+  },
+
+  getGroupEffortSummary: async (groupId: string, includeSubgroups?: boolean): Promise<GroupEffortSummary> => {
+    return await invoke("get_group_effort_summary", { groupId, includeSubgroups });
+  },
+
+  // 统计
+  getStats: async (range?: StatsRange): Promise<Stats> => {
+    return await invoke("get_stats", { range });
+  },
+
+  // 番茄钟
+  startPomodoro: async (todoId?: string): Promise<PomodoroStatus> => {
+    return await invoke("start_pomodoro", { todoId });
+  },
+
+  stopPomodoro: async (): Promise<PomodoroStatus | null> => {
+    return await invoke("stop_pomodoro");
+  },
+
+  getPomodoroStatus: async (): Promise<PomodoroStatus | null> => {
+    return await invoke("get_pomodoro_status");
+  },
+
+  getFocusStats: async (from?: string, to?: string): Promise<FocusStats> => {
+    return await invoke("get_focus_stats", { from, to });
+  },
+
+  duplicateTodo: async (id: string, options?: DuplicateOptions): Promise<TodoItem[]> => {
+    return await invoke("duplicate_todo", { id, options });
+  },
+
   reorderTodos: async (todoIds: string[]): Promise<void> => {
     return await invoke("reorder_todos", { todoIds });
   },
 
+  // 把待办放到同级待办 afterId 之后，不传时放到最前
+  repositionTodo: async (id: string, afterId?: string): Promise<TodoItem> => {
+    return await invoke("reposition_todo", { id, afterId });
+  },
+
+  // 子待办随父待办一起移动；position 为目标分组中的位置，不传时放到末尾
+  moveTodos: async (ids: string[], targetGroupId: string, position?: number): Promise<TodoItem[]> => {
+    return await invoke("move_todos", { ids, targetGroupId, position });
+  },
+
+  searchTodos: async (query?: string, filter?: TodoFilter, sortBy?: SortKey[]): Promise<TodoItem[]> => {
+    return await invoke("search_todos", { query, filter, sortBy });
+  },
+
+  sortTodos: async (groupId: string, sortBy?: SortKey[], persist?: boolean): Promise<TodoItem[]> => {
+    return await invoke("sort_todos", { groupId, sortBy, persist });
+  },
+
+  // 标签操作
+  listTags: async (): Promise<TagCount[]> => {
+    return await invoke("list_tags");
+  },
+
+  renameTag: async (oldName: string, newName: string): Promise<number> => {
+    return await invoke("rename_tag", { oldName, newName });
+  },
+
+  mergeTags: async (sources: string[], target: string): Promise<number> => {
+    return await invoke("merge_tags", { sources, target });
+  },
+
+  deleteTag: async (name: string): Promise<number> => {
+    return await invoke("delete_tag", { name });
+  },
+
   // 时间节点操作
   addTimeNode: async (
     todoId: string,
     dateTime: string,
     description: string | null,
-    reminderEnabled: boolean,
-    reminderMinutesBefore: number
+    // 不传时使用分组的默认提醒
+    reminderEnabled?: boolean,
+    reminderMinutesBefore?: number
   ): Promise<TimeNode> => {
     return await invoke("add_time_node", {
       todoId,
@@ -92,20 +320,49 @@ export const api = {
   },
 
   // 分组操作
-  createGroup: async (name: string): Promise<TodoGroup> => {
-    return await invoke("create_group", { name });
+  createGroup: async (name: string, parentGroupId?: string): Promise<TodoGroup> => {
+    return await invoke("create_group", { name, parentGroupId });
+  },
+
+  // parentGroupId、color、icon、description 传空字符串表示清除
+  updateGroup: async (
+    id: string,
+    name: string,
+    updates: {
+      parentGroupId?: string;
+      color?: string;
+      icon?: string;
+      description?: string;
+      defaults?: GroupDefaults;
+    } = {}
+  ): Promise<TodoGroup> => {
+    return await invoke("update_group", {
+      id,
+      name,
+      parentGroupId: updates.parentGroupId,
+      color: updates.color,
+      icon: updates.icon,
+      description: updates.description,
+      defaults: updates.defaults,
+    });
   },
 
-  updateGroup: async (id: string, name: string): Promise<TodoGroup> => {
-    return await invoke("update_group", { id, name });
+  setGroupCustomFields: async (groupId: string, fields: CustomFieldDef[]): Promise<TodoGroup> => {
+    return await invoke("set_group_custom_fields", { groupId, fields });
   },
 
-  deleteGroup: async (id: string, moveToPersonal: boolean): Promise<void> => {
-    return await invoke("delete_group", { id, moveToPersonal });
+  // moveTodos 为 true 时待办移到 moveTo，未指定 moveTo 时移到默认分组
+  deleteGroup: async (id: string, moveTodos: boolean, moveTo?: string): Promise<void> => {
+    return await invoke("delete_group", { id, moveTodos, moveTo });
   },
 
-  reorderGroups: async (groupIds: string[]): Promise<void> => {
-    return await invoke("reorder_groups", { groupIds });
+  setDefaultGroup: async (id: string): Promise<void> => {
+    return await invoke("set_default_group", { id });
+  },
+
+  // parentGroupId 为空时调整顶层分组
+  reorderGroups: async (groupIds: string[], parentGroupId?: string): Promise<void> => {
+    return await invoke("reorder_groups", { groupIds, parentGroupId });
   },
 
   // 设置操作
@@ -114,12 +371,53 @@ export const api = {
   },
 
   // 导入导出
-  exportData: async (path: string): Promise<void> => {
-    return await invoke("export_data", { path });
+  exportData: async (path: string, options?: ExportOptions): Promise<void> => {
+    return await invoke("export_data", { path, options });
+  },
+
+  readExportInfo: async (path: string): Promise<ExportInfo> => {
+    return await invoke("read_export_info", { path });
+  },
+
+  importData: async (path: string, merge?: boolean): Promise<void> => {
+    return await invoke("import_data", { path, merge });
+  },
+
+  // 返回标题中导入时会被当作扩展字段的说明
+  exportTodoTxt: async (path: string): Promise<string[]> => {
+    return await invoke("export_todotxt", { path });
+  },
+
+  importTodoTxt: async (path: string): Promise<ImportSummary> => {
+    return await invoke("import_todotxt", { path });
+  },
+
+  exportCsv: async (path: string): Promise<void> => {
+    return await invoke("export_csv", { path });
+  },
+
+  readCsvHeaders: async (path: string): Promise<string[]> => {
+    return await invoke("read_csv_headers", { path });
+  },
+
+  importCsv: async (path: string, mapping: CsvColumnMapping): Promise<ImportSummary> => {
+    return await invoke("import_csv", { path, mapping });
+  },
+
+  exportOrg: async (path: string): Promise<void> => {
+    return await invoke("export_org", { path });
+  },
+
+  importTodoist: async (path: string): Promise<ImportSummary> => {
+    return await invoke("import_todoist", { path });
+  },
+
+  importMicrosoftTodo: async (path: string): Promise<ImportSummary> => {
+    return await invoke("import_microsoft_todo", { path });
   },
 
-  importData: async (path: string): Promise<void> => {
-    return await invoke("import_data", { path });
+  exportReport: async (path: string, options?: ReportOptions): Promise<void> => {
+    return await invoke("export_report", { path, options });
   },
 
   // 窗口位置和边缘停靠
@@ -5,24 +5,84 @@ export interface TodoItem {
   group_id: string;
   color_tag: ColorTag;
   completed: boolean;
+  completed_at?: string;
   hidden: boolean;
   archived: boolean;
   archived_at?: string;
+  // 同一分组、同一父待办下的顺序，可以是小数
   order: number;
   parent_id?: string;
   time_nodes: TimeNode[];
+  tags: string[];
+  priority: Priority;
+  start_date?: string;
+  due_date?: string;
+  pomodoros: number;
+  estimate_minutes?: number;
+  blocked_by: string[];
+  attachments: Attachment[];
+  history: HistoryEntry[];
+  // 键为所属分组中的字段 id
+  custom_fields: Record<string, CustomFieldValue>;
   created_at: string;
   updated_at: string;
 }
 
+// 按 kind 区分的附件；File 的 copied 为 true 时文件存放在应用数据目录
+export type Attachment = {
+  id: string;
+  created_at: string;
+} & (
+  | { kind: "File"; path: string; name: string; copied: boolean }
+  | { kind: "Url"; url: string; title?: string }
+  | { kind: "TodoRef"; todo_id: string }
+);
+
+export interface HistoryEntry {
+  field: string;
+  old_value?: string;
+  new_value?: string;
+  at: string;
+}
+
 export interface TodoGroup {
   id: string;
   name: string;
+  // 上级分组，order 为同一上级下的顺序
+  parent_group_id?: string;
   order: number;
   is_default: boolean;
+  // 十六进制颜色，如 "#4a90e2"
+  color?: string;
+  // emoji 或图标名
+  icon?: string;
+  description?: string;
+  defaults: GroupDefaults;
+  custom_fields: CustomFieldDef[];
   created_at: string;
 }
 
+// 组内新建待办和时间节点未指定时使用的默认值
+export interface GroupDefaults {
+  color_tag?: ColorTag;
+  reminder_minutes_before?: number;
+}
+
+// 新字段的 id 传空字符串，由后端生成
+export type CustomFieldDef = {
+  id: string;
+  name: string;
+} & (
+  | { type: "Text" }
+  | { type: "Number" }
+  | { type: "Date" }
+  | { type: "Select"; options: string[] }
+  | { type: "Checkbox" }
+);
+
+// Date 为 RFC 3339 字符串，Select 为选项文本
+export type CustomFieldValue = string | number | boolean;
+
 export interface TimeNode {
   id: string;
   date_time: string;
@@ -42,6 +102,16 @@ export enum ColorTag {
   Purple7 = "Purple7",
 }
 
+export enum Priority {
+  None = "None",
+  Low = "Low",
+  Medium = "Medium",
+  High = "High",
+  Urgent = "Urgent",
+}
+
+export type SortKey = "DueDate" | "StartDate" | "Priority" | "Order" | "CreatedAt" | "Title";
+
 export enum Theme {
   White = "White",
   MilkWhite = "MilkWhite",
@@ -89,10 +159,245 @@ export interface AppSettings {
   remember_window_size: boolean;
   window_position: WindowPosition;
   default_docked_edge: DockedEdge;
+  pomodoro: PomodoroSettings;
+}
+
+export interface PomodoroSettings {
+  work_minutes: number;
+  short_break_minutes: number;
+  long_break_minutes: number;
+  long_break_interval: number;
 }
 
 export interface AppData {
   todos: TodoItem[];
   groups: TodoGroup[];
   settings: AppSettings;
+  time_entries: TimeEntry[];
+  focus_sessions: FocusSession[];
+  templates: TodoTemplate[];
+  version: number;
+}
+
+// 归档待办的一页，page 从 1 开始
+export interface ArchivedPage {
+  todos: TodoItem[];
+  total: number;
+  page: number;
+  page_size: number;
+}
+
+// offset 相对实例化时的基准日期，如 "+2d 09:00"、"-1w"、"+3h"
+export interface TemplateTimeNode {
+  offset: string;
+  description?: string;
+  reminder_enabled: boolean;
+  reminder_minutes_before: number;
+}
+
+export interface TemplateItem {
+  title: string;
+  details?: string;
+  color_tag: ColorTag;
+  priority: Priority;
+  tags: string[];
+  estimate_minutes?: number;
+  start_offset?: string;
+  due_offset?: string;
+  time_nodes: TemplateTimeNode[];
+  custom_fields: Record<string, CustomFieldValue>;
+  children: TemplateItem[];
+}
+
+// date_offset 与模板偏移写法相同，如 "+7d"
+export interface DuplicateOptions {
+  include_children?: boolean;
+  date_offset?: string;
+}
+
+export interface TodoTemplate {
+  id: string;
+  name: string;
+  items: TemplateItem[];
+  created_at: string;
+  updated_at: string;
+}
+
+// end 为空表示正在计时
+export interface TimeEntry {
+  id: string;
+  todo_id: string;
+  start: string;
+  end?: string;
+  note?: string;
+  // 结束计时时的待办标题和分组，待办删除后仍保留
+  title?: string;
+  group_id?: string;
+}
+
+export interface TimeTotal {
+  key: string;
+  seconds: number;
+}
+
+export enum PomodoroPhase {
+  Work = "Work",
+  ShortBreak = "ShortBreak",
+  LongBreak = "LongBreak",
+}
+
+// "pomodoro-changed" 事件内容，null 表示已停止
+export interface PomodoroStatus {
+  todo_id?: string;
+  phase: PomodoroPhase;
+  started_at: string;
+  ends_at: string;
+  completed_in_cycle: number;
+}
+
+export interface FocusSession {
+  id: string;
+  todo_id?: string;
+  start: string;
+  end: string;
+  completed: boolean;
+}
+
+export interface FocusStats {
+  completed_pomodoros: number;
+  interrupted_pomodoros: number;
+  focus_seconds: number;
+  by_todo: TimeTotal[];
+  by_day: TimeTotal[];
+}
+
+export interface EffortRollup {
+  todo_id: string;
+  estimate_minutes: number;
+  remaining_minutes: number;
+  tracked_minutes: number;
+}
+
+export interface GroupEffortSummary {
+  group_id: string;
+  estimate_minutes: number;
+  remaining_minutes: number;
+  tracked_minutes: number;
+  open_todos: number;
+  unestimated_todos: number;
+}
+
+export enum StatsBucket {
+  Day = "Day",
+  Week = "Week",
+}
+
+export interface StatsRange {
+  from?: string;
+  to?: string;
+  bucket?: StatsBucket;
+}
+
+export interface PeriodCount {
+  period: string;
+  created: number;
+  completed: number;
+}
+
+export interface BreakdownCount {
+  key: string;
+  created: number;
+  completed: number;
+}
+
+export interface Stats {
+  periods: PeriodCount[];
+  created: number;
+  completed: number;
+  average_lead_time_hours?: number;
+  by_group: BreakdownCount[];
+  by_color: BreakdownCount[];
+  current_streak_days: number;
+}
+
+export interface TimeTotals {
+  by_todo: TimeTotal[];
+  by_group: TimeTotal[];
+  by_day: TimeTotal[];
+}
+
+export interface ImportSummary {
+  todos_imported: number;
+  groups_created: number;
+  unmapped: string[];
+}
+
+export interface CsvColumnMapping {
+  title: string;
+  details?: string;
+  group?: string;
+  color?: string;
+  tags?: string;
+  priority?: string;
+  start_date?: string;
+  due_date?: string;
+  completed?: string;
+  completed_at?: string;
+  archived?: string;
+  created_at?: string;
+  updated_at?: string;
+  time_node?: string;
+}
+
+export enum DateField {
+  Created = "Created",
+  Updated = "Updated",
+  Completed = "Completed",
+}
+
+export interface TodoFilter {
+  group_ids?: string[];
+  include_archived?: boolean;
+  include_completed?: boolean;
+  date_field?: DateField;
+  from?: string;
+  to?: string;
+  tags?: string[];
+  // group_ids 是否包含子孙分组
+  include_subgroups?: boolean;
+}
+
+export interface ExportOptions {
+  filter?: TodoFilter;
+  include_settings?: boolean;
+}
+
+// 导出文件概要；partial 为部分导出，只能合并导入
+export interface ExportInfo {
+  partial: boolean;
+  todos: number;
+  groups: number;
+}
+
+export interface ReportOptions {
+  filter?: TodoFilter;
+  title?: string;
+}
+
+// "todos-unblocked" 事件内容
+export interface TodosUnblocked {
+  blocker_id: string;
+  todo_ids: string[];
+}
+
+// "data-changed" 事件内容：查不到的 id 表示已删除，reload 为 true 时需重新加载全部数据
+export interface DataChanged {
+  todo_ids: string[];
+  group_ids: string[];
+  reload: boolean;
+}
+
+export interface TagCount {
+  name: string;
+  count: number;
 }